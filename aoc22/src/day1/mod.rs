@@ -58,7 +58,7 @@ fn read_elves(elves: &mut Vec<Elf>, input: &String) {
     }
 }
 
-#[aoc(day1, part1)]
+#[aoc(day1, part1, title = "Calorie Counting")]
 pub fn part1(input: String) -> String {
     let mut elves: Vec<Elf> = vec![];
 
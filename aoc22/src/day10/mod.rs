@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use aoclib::ocr;
 
 #[derive(Debug)]
 enum Opcodes {
@@ -110,7 +111,7 @@ fn parse_insn(input: &String) -> InsnList {
     insn
 }
 
-#[aoc(day=10, part=1)]
+#[aoc(day=10, part=1, title = "Cathode-Ray Tube")]
 fn part1(input: String) -> String {
     let insn = parse_insn(&input);
     let mut state = ProgramState { x: 1 };
@@ -123,6 +124,9 @@ fn part2(input: String) -> String {
     let insn = parse_insn(&input);
     let mut state = ProgramState { x: 1 };
 
-    state.print_run(&insn)
+    let screen = state.print_run(&insn);
+
+    // not every program draws letters, like the puzzle's striped example; show those as drawn
+    ocr::recognize(&screen).unwrap_or(screen)
 }
 
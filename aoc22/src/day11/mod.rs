@@ -1,6 +1,6 @@
 use std::collections::{HashMap, VecDeque};
-use lazy_static::lazy_static;
-use regex::Regex;
+use aoclib::{math, parse};
+use aoclib::expr::Expr;
 
 type MonkeyId = i32;
 type ItemWorryLevel = i64;
@@ -15,46 +15,39 @@ struct Monkey {
 }
 
 fn parse_monkeys(input: &String) -> (Vec<Monkey>, ItemWorryLevel) {
-    lazy_static! {
-        static ref SPLIT_REGEX: Regex = Regex::new(r"\r?\n\r?\n").unwrap();
-        static ref MONKEY_REGEX: Regex = Regex::new(r"Monkey (\d+):\r?\n  Starting items: ([\w, ]+)\r?\n  Operation: new = (\w+) ([+\-*/]) (\w+)\r?\n  Test: divisible by (\d+)\r?\n    If true: throw to monkey (\d+)\r?\n    If false: throw to monkey (\d+)").unwrap();
-    }
-
     let mut monkeys: Vec<Monkey> = Vec::new();
-    let mut cm: ItemWorryLevel = 1;
-    let split = SPLIT_REGEX.split(input);
-
-    for mstr in split {
-        let m = MONKEY_REGEX.captures(mstr).unwrap_or_else(|| panic!("couldn't parse regex"));
-        let arg1: Option<ItemWorryLevel> = m[3].parse().ok();
-        let op = m[4].chars().next().unwrap();
-        let arg2: Option<ItemWorryLevel> = m[5].parse().ok();
-        let modulus: ItemWorryLevel = m[6].parse().unwrap();
-        let when_true: MonkeyId = m[7].parse().unwrap();
-        let when_false: MonkeyId = m[8].parse().unwrap();
+    let mut moduli: Vec<ItemWorryLevel> = Vec::new();
+    for mstr in parse::paragraphs(input) {
+        let mut lines = mstr.lines().map(str::trim);
+        let mut line = || lines.next().unwrap_or_else(|| panic!("monkey description is cut short:\n{mstr}"));
+        let fail = |e: parse::ScanError| -> ! { panic!("couldn't parse monkey: {e}") };
+
+        let (id,): (MonkeyId,) = scan!(line(), "Monkey {}:").unwrap_or_else(|e| fail(e));
+        let (items,): (String,) = scan!(line(), "Starting items: {}").unwrap_or_else(|e| fail(e));
+        let (operation,): (String,) = scan!(line(), "Operation: new = {}").unwrap_or_else(|e| fail(e));
+        let (modulus,): (ItemWorryLevel,) = scan!(line(), "Test: divisible by {}").unwrap_or_else(|e| fail(e));
+        let (when_true,): (MonkeyId,) = scan!(line(), "If true: throw to monkey {}").unwrap_or_else(|e| fail(e));
+        let (when_false,): (MonkeyId,) = scan!(line(), "If false: throw to monkey {}").unwrap_or_else(|e| fail(e));
+
+        let operation = Expr::parse(&operation).unwrap_or_else(|e| panic!("couldn't parse operation: {e}"));
+        if let Some(name) = operation.variables().into_iter().find(|&name| name != "old") {
+            panic!("operation {operation} refers to {name}, but only old is known");
+        }
 
-        cm *= &modulus;
+        moduli.push(modulus);
 
         monkeys.push(Monkey {
-            id: m[1].parse().unwrap(),
-            items: m[2].split(", ").map(|item| item.parse::<ItemWorryLevel>().unwrap()).collect(),
-            operation: Box::new(move |value| -> ItemWorryLevel {
-                let l = arg1.clone().unwrap_or(value.clone());
-                let r = arg2.clone().unwrap_or(value.clone());
-
-                match op {
-                    '+' => l + r,
-                    '-' => l - r,
-                    '*' => l * r,
-                    '/' => l / r,
-                    _ => panic!("unsupported op {op}")
-                }
+            id,
+            items: parse::ints(&items).unwrap_or_else(|e| panic!("couldn't parse monkey {id}'s items: {e}")).into(),
+            operation: Box::new(move |value| {
+                operation.eval(|_| Some(value)).unwrap_or_else(|e| panic!("couldn't evaluate {operation} with old = {value}: {e}"))
             }),
             next_monkey: Box::new(move |value| if value % &modulus == 0 { when_true } else { when_false }),
             inspected: 0
         });
     }
 
+    let cm = math::lcm_all(&moduli).expect("common multiple of all modules overflows");
     println!("Of all modules, common multiple is {cm}");
 
     (monkeys, cm)
@@ -108,7 +101,7 @@ fn report_monkey_business(monkeys: &Vec<Monkey>) -> InspectionCount {
     sorted.get(0).unwrap().0 * sorted.get(1).unwrap().0
 }
 
-#[aoc(day=11, part=1)]
+#[aoc(day=11, part=1, title = "Monkey in the Middle")]
 fn part1(input: String) -> String {
     let (mut monkeys, lcm) = parse_monkeys(&input);
 
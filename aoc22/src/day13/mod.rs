@@ -121,7 +121,7 @@ fn parse_flat(input: &String) -> Vec<PacketData> {
     data
 }
 
-#[aoc(day=13, part=1)]
+#[aoc(day=13, part=1, title = "Distress Signal")]
 fn part1(input: String) -> String {
     let pairs = parse_pairs(&input);
     let mut right_sum = 0;
@@ -108,7 +108,7 @@ fn input_fill_cave(input: &String, cave: &mut Cave) {
     }
 }
 
-#[aoc(day=14, part=1)]
+#[aoc(day=14, part=1, title = "Regolith Reservoir")]
 fn part1(input: String) -> String {
     let mut cave = Cave {
         grid: HashMap::new(),
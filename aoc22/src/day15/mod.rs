@@ -1,16 +1,11 @@
-use std::collections::HashSet;
 use std::ops::RangeInclusive;
 use lazy_static::lazy_static;
 use regex::Regex;
+use aoclib::geom::Point2;
+use aoclib::ranges::RangeSet;
 
 type CoordinateType = i32;
-const DELTA_SIGNS: [[CoordinateType; 2]; 4] = [[-1, -1], [1, -1], [-1, 1], [1, 1]];
-
-#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
-struct Coord {
-    x: CoordinateType,
-    y: CoordinateType,
-}
+type Coord = Point2<CoordinateType>;
 
 #[derive(Copy, Clone, Eq, PartialEq)]
 struct Signal {
@@ -18,17 +13,7 @@ struct Signal {
     dist: CoordinateType,
 }
 
-impl Coord {
-    fn dist(&self, other: &Coord) -> CoordinateType {
-        (self.x - other.x).abs() + (self.y - other.y).abs()
-    }
-}
-
 impl Signal {
-    fn contains(&self, other: &Coord) -> bool {
-        self.pos.dist(other) <= self.dist
-    }
-
     /**
      * Return the range of x values that this Signal contains at the given y value.
      */
@@ -38,25 +23,9 @@ impl Signal {
         self.pos.x-remaining_dist..=self.pos.x+remaining_dist
     }
 
-    fn get_edges(&self, into: &mut HashSet<Coord>) {
-        let max_dist = self.dist + 1;
-
-        for dx in 0..=max_dist {
-            let dy = max_dist - dx;
-
-            // note that this technically makes two redundant insertions when one of dx or dy is zero
-            for [mx, my] in DELTA_SIGNS {
-                into.insert(Coord {
-                    x: self.pos.x + dx * mx,
-                    y: self.pos.y + dy * my
-                });
-            }
-        }
-    }
-
     fn from_distance_to(point: Coord, other: &Coord) -> Signal {
         Signal {
-            dist: point.dist(other),
+            dist: point.manhattan(other),
             pos: point,
         }
     }
@@ -91,47 +60,42 @@ fn parse_signals(input: &String) -> (Vec<Signal>, Vec<Coord>) {
 const DESIRED_Y: CoordinateType = 2_000_000;
 const COORD_LIMIT: CoordinateType = DESIRED_Y * 2;
 
-#[aoc(day=15, part=1)]
+#[aoc(day=15, part=1, title = "Beacon Exclusion Zone")]
 fn part1(input: String) -> String {
     let (signals, beacons) = parse_signals(&input);
-    let mut set: HashSet<CoordinateType> = HashSet::new();
-
-    for range in signals.iter().map(|signal| signal.contained_at_y(DESIRED_Y)) {
-        set.extend(range);
-    }
+    let mut set: RangeSet<CoordinateType> = signals.iter().map(|signal| signal.contained_at_y(DESIRED_Y)).collect();
 
     for coord in beacons.iter().filter(|coord| coord.y == DESIRED_Y) {
-        set.remove(&coord.x);
+        set.remove_value(coord.x);
     }
 
-    set.len().to_string()
+    set.total_len().to_string()
 }
 
 #[aoc(day=15, part=2)]
 fn part2(input: String) -> String {
-    let (signals, beacons) = parse_signals(&input);
-    let mut edges = HashSet::new();
-
-    for signal in signals.iter() {
-        signal.get_edges(&mut edges);
-    }
-
-    for coord in beacons.iter() {
-        edges.remove(coord);
-    }
-
-    edges.retain(|coord| {
-        if coord.x < 0 || coord.x > COORD_LIMIT || coord.y < 0 || coord.y > COORD_LIMIT {
-            return false;
+    let (signals, _) = parse_signals(&input);
+    let mut candidates = Vec::new();
+    let mut covered = RangeSet::new();
+
+    // every beacon is covered by its own sensor, so any gap left in a row is a position no sensor can see.
+    // two positions are enough to know the answer is ambiguous, so stop there rather than listing them all
+    'rows: for y in 0..=COORD_LIMIT {
+        covered.clear();
+        covered.extend(signals.iter().map(|signal| signal.contained_at_y(y)));
+
+        for gap in covered.gaps(0..=COORD_LIMIT) {
+            candidates.extend(gap.map(|x| Coord { x, y }).take(2 - candidates.len()));
+            if candidates.len() == 2 {
+                break 'rows;
+            }
         }
+    }
 
-        !signals.iter().any(|signal| signal.contains(coord))
-    });
-
-    let result = match edges.len() {
+    let result = match candidates.len() {
         0 => return "search yielded no beacons".to_string(),
-        1 => edges.iter().next().unwrap(),
-        _ => return format!("search yielded multiple beacons: {edges:?}")
+        1 => candidates[0],
+        _ => return format!("search yielded multiple beacons, starting with {candidates:?}")
     };
 
     println!("yielded beacon: {result:?}");
@@ -1,306 +1,373 @@
-use std::cell::{Cell, RefCell};
-use std::collections::{HashMap, HashSet};
-use std::rc::Rc;
+use std::collections::HashMap;
+use std::fmt;
 use lazy_static::lazy_static;
 use regex::Regex;
 
-type SharedValve = Rc<RefCell<Valve>>;
-type PathCache = Box<RefCell<HashMap<String, Option<u32>>>>;
-
 struct Valve {
     name: String,
-    flow: i32,
-    connected: HashMap<String, SharedValve>,
-    cached_paths: PathCache,
+    flow: u32,
+    tunnels: Vec<String>,
 }
 
-impl Valve {
-    fn get_path_length(&self, goal: &Valve) -> Option<u32> {
-        if let Some(cached) = self.cached_paths.borrow().get(&goal.name) {
-            // return cached result
-            return cached.clone();
-        }
-
-        // otherwise, calculate and cache
-        let path = self.force_calc_path_length(goal);
-
-        self.cached_paths.borrow_mut().insert(goal.name.clone(), path.clone());
-        goal.cached_paths.borrow_mut().insert(self.name.clone(), path.clone());
-
-        path
-    }
-
-    fn force_calc_path_length(&self, goal: &Valve) -> Option<u32> {
-        // conduct a breadth-first search to find the shortest path between Valves
-        let mut current: Vec<HashMap<String, SharedValve>> = vec![self.connected.clone()];
-        let mut visited: HashSet<String> = HashSet::from([self.name.clone()]);
-        let mut depth = 0;
-        let mut next: Vec<HashMap<String, SharedValve>> = Vec::new();
-
-        loop {
-            depth += 1;
-
-            while let Some(connections) = current.pop() {
-                for connected_sv in connections.values() {
-                    let connected_valve = connected_sv.borrow();
+struct Graph {
+    names: Vec<String>,
+    flows: Vec<u32>,
+    /// shortest travel time in minutes between every pair of valves, `u32::MAX` if unreachable
+    distances: Vec<Vec<u32>>,
+    /// the first valve to walk to from `i` on a shortest route to `j`
+    next_hops: Vec<Vec<usize>>,
+    /// valves worth opening (flow > 0); bit `i` of an opened-set mask refers to `significant[i]`
+    significant: Vec<usize>,
+}
 
-                    if connected_valve.name == goal.name {
-                        return Some(depth);
-                    }
+impl Graph {
+    fn new(valves: Vec<Valve>) -> Graph {
+        let index: HashMap<&str, usize> = valves.iter().enumerate().map(|(i, valve)| (valve.name.as_str(), i)).collect();
+        let n = valves.len();
+
+        // Floyd–Warshall over every valve, so zero-flow valves only matter as travel time
+        let mut distances = vec![vec![u32::MAX; n]; n];
+        let mut next_hops: Vec<Vec<usize>> = (0..n).map(|i| vec![i; n]).collect();
+        for (i, valve) in valves.iter().enumerate() {
+            distances[i][i] = 0;
+
+            for tunnel in valve.tunnels.iter() {
+                let j = *index.get(tunnel.as_str()).unwrap_or_else(|| panic!("valve {} leads to unknown valve {tunnel}", valve.name));
+                distances[i][j] = 1;
+                next_hops[i][j] = j;
+            }
+        }
 
-                    if visited.insert(connected_valve.name.clone()) {
-                        next.push(connected_valve.connected.clone());
+        for k in 0..n {
+            for i in 0..n {
+                for j in 0..n {
+                    let through = distances[i][k].saturating_add(distances[k][j]);
+                    if through < distances[i][j] {
+                        distances[i][j] = through;
+                        next_hops[i][j] = next_hops[i][k];
                     }
                 }
             }
+        }
 
-            if next.is_empty() {
-                return None;
-            }
+        let mut significant: Vec<usize> = (0..n).filter(|&i| valves[i].flow > 0).collect();
+        significant.sort_by(|&a, &b| valves[a].name.cmp(&valves[b].name));
 
-            next.clone_into(&mut current);
-            next.clear();
+        Graph {
+            names: valves.iter().map(|valve| valve.name.clone()).collect(),
+            flows: valves.iter().map(|valve| valve.flow).collect(),
+            distances,
+            next_hops,
+            significant,
         }
     }
-}
 
-#[derive(Clone)]
-struct Action {
-    minute_cost: u32,
-    pressure_released: i32,
-}
-
-impl Action {
-    fn consider(start: SharedValve, dest: SharedValve, minutes_remaining: i32) -> Option<Action> {
-        let valve = dest.borrow();
+    fn index_of(&self, name: &str) -> usize {
+        self.names.iter().position(|n| n == name).unwrap_or_else(|| panic!("no valve named {name}"))
+    }
 
-        if valve.flow < 1 {
-            // take no action, as there is nothing to gain
-            return None;
+    /// The valves walked through on a shortest route, excluding `from` and ending with `to`.
+    fn route(&self, from: usize, to: usize) -> Vec<usize> {
+        let mut route = Vec::new();
+        let mut at = from;
+        while at != to {
+            at = self.next_hops[at][to];
+            route.push(at);
         }
 
-        let minute_cost = match start.borrow().get_path_length(&valve) {
-            Some(movement_cost) => movement_cost + 1,
-            None => return None
-        };
-        let pressure_released = (minutes_remaining - minute_cost as i32) * valve.flow;
+        route
+    }
 
-        if pressure_released <= 0 {
-            // take no action, as we don't have time to benefit
-            return None;
-        }
+    fn agent_plan(&self, agent: &Agent, plan: &Plan) -> AgentPlan {
+        let mut at = self.index_of(&agent.start);
+        let mut actions = Vec::new();
 
-        Some(Action {
-            minute_cost,
-            pressure_released
-        })
-    }
+        for &(valve, _) in plan.openings.iter() {
+            actions.extend(self.route(at, valve).into_iter().map(|step| Action::Move(self.names[step].clone())));
+            actions.push(Action::Open(self.names[valve].clone()));
+            at = valve;
+        }
 
-    fn noop() -> Action {
-        Action {
-            minute_cost: 0,
-            pressure_released: 0,
+        AgentPlan {
+            start: agent.start.clone(),
+            minutes: agent.minutes,
+            openings: plan.openings.iter().map(|&(valve, minute)| Opening {
+                valve: self.names[valve].clone(),
+                flow: self.flows[valve],
+                minute
+            }).collect(),
+            actions,
         }
     }
-}
 
-struct Graph {
-    significant_nodes: HashMap<String, SharedValve>,
-    start: SharedValve,
-    minute_limit: i32,
-}
-
-impl Graph {
-    fn find_optimal_moves(&self) -> Option<i32> {
-        // conduct a depth-first search to find the optimal sequence of moves
-        let max = Cell::new(None);
+    /// The best way to open each set of valves in `minutes` from `start`, indexed by opened-set
+    /// mask, or `None` for sets that can't all be opened in time.
+    fn plans_by_opened(&self, start: usize, minutes: u32) -> Vec<Option<Plan>> {
+        let mut plans = vec![None; 1 << self.significant.len()];
+        self.visit(start, minutes, 0, 0, &mut Vec::new(), &mut plans);
 
-        self.find_next_optimal_move(Rc::clone(&self.start), &mut Vec::new(), &max);
+        // openings were recorded by minutes remaining; report the minute each valve is opened instead
+        for plan in plans.iter_mut().flatten() {
+            for (_, minute) in plan.openings.iter_mut() {
+                *minute = minutes - *minute;
+            }
+        }
 
-        max.get()
+        plans
     }
 
-    fn find_next_optimal_move(&self, from: SharedValve, action_stack: &mut Vec<(String, Action)>, max: &Cell<Option<i32>>) {
-        let minutes_remaining = self.minute_limit - action_stack.iter().map(|(_, action)| action.minute_cost).sum::<u32>() as i32;
-        let mut end = true;
+    fn visit(&self, at: usize, minutes_remaining: u32, opened: usize, released: u32, openings: &mut Vec<(usize, u32)>, plans: &mut [Option<Plan>]) {
+        if plans[opened].as_ref().is_none_or(|plan| released > plan.released) {
+            plans[opened] = Some(Plan { released, openings: openings.clone() });
+        }
 
-        for (name, candidate) in self.significant_nodes.iter() {
-            if action_stack.iter().any(|(visited, _)| visited == name) {
-                // we've already visited this valve
+        for (bit, &valve) in self.significant.iter().enumerate() {
+            if opened & (1 << bit) != 0 {
                 continue;
             }
 
-            if let Some(action) = Action::consider(Rc::clone(&from), Rc::clone(candidate), minutes_remaining) {
-                end = false;
-                action_stack.push((name.clone(), action));
+            // travel there and spend a minute opening it; it has to be open for at least a minute to help
+            let minute_cost = self.distances[at][valve].saturating_add(1);
+            if minute_cost >= minutes_remaining {
+                continue;
+            }
 
-                // proceed to this valve and continue from there
-                self.find_next_optimal_move(Rc::clone(candidate), action_stack, max);
+            let remaining = minutes_remaining - minute_cost;
+            openings.push((valve, remaining));
+            self.visit(valve, remaining, opened | (1 << bit), released + remaining * self.flows[valve], openings, plans);
+            openings.pop();
+        }
+    }
 
-                action_stack.pop();
-            }
+    /// Splits the valves between any number of agents working at once, each opening a disjoint
+    /// set, and finds the split and schedules releasing the most pressure in total. Fails if more
+    /// than `max_valves` valves have a flow rate.
+    fn solve(&self, agents: &[Agent], max_valves: usize) -> Result<Solution, TooManyValves> {
+        // a mask needs a bit per valve, besides the limit asked for
+        let limit = max_valves.min(usize::BITS as usize - 1);
+        if self.significant.len() > limit {
+            return Err(TooManyValves { valves: self.significant.len(), limit });
         }
 
-        if end {
-            // when there's no more progress to be made, record our result
-            let total_released = action_stack.iter().map(|(_, action)| action.pressure_released).sum();
+        let masks = 1 << self.significant.len();
+
+        // best[mask]: the most the agents considered so far can release by opening exactly `mask`
+        let mut best: Vec<Option<u32>> = vec![None; masks];
+        best[0] = Some(0);
+        let mut steps: Vec<(Vec<Option<Plan>>, Vec<usize>)> = Vec::new();
+
+        for agent in agents {
+            let plans = self.plans_by_opened(self.index_of(&agent.start), agent.minutes);
+            let mut next: Vec<Option<u32>> = vec![None; masks];
+            let mut chosen = vec![0; masks];
+
+            for mask in 0..masks {
+                // try every subset of `mask` as this agent's share, including none at all
+                let mut share = mask;
+                loop {
+                    if let (Some(others), Some(plan)) = (best[mask ^ share], &plans[share]) {
+                        let total = others + plan.released;
+                        if next[mask].is_none_or(|current| total > current) {
+                            next[mask] = Some(total);
+                            chosen[mask] = share;
+                        }
+                    }
+
+                    if share == 0 {
+                        break;
+                    }
+                    share = (share - 1) & mask;
+                }
+            }
 
-            max.set(Some(max.get().map_or(total_released, |former_max| former_max.max(total_released))));
+            best = next;
+            steps.push((plans, chosen));
         }
-    }
 
-    fn find_optimal_moves_with_elephant(&self) -> Option<i32> {
-        // conduct a depth-first search with two cursors to find the optimal sequence of moves with an elephant helper
-        let max = Cell::new(None);
+        let (mut mask, released) = best.iter().enumerate()
+            .filter_map(|(mask, released)| Some((mask, (*released)?)))
+            .max_by_key(|&(_, released)| released)
+            .unwrap();
 
-        self.find_next_optimal_move_with_elephant(Rc::clone(&self.start), Rc::clone(&self.start), &mut Vec::new(), &mut Vec::new(), &max);
+        let mut agent_plans = Vec::new();
+        for (agent, (plans, chosen)) in agents.iter().zip(steps.iter()).rev() {
+            let share = chosen[mask];
+            agent_plans.push(self.agent_plan(agent, plans[share].as_ref().unwrap()));
+            mask ^= share;
+        }
+        agent_plans.reverse();
 
-        max.get()
+        Ok(Solution { released, plans: agent_plans })
     }
+}
 
-    fn find_next_optimal_move_with_elephant(&self, from_1: SharedValve, from_2: SharedValve, action_stack_1: &mut Vec<(String, Action)>, action_stack_2: &mut Vec<(String, Action)>, max: &Cell<Option<i32>>) {
-        // TODO this is horribly unoptimized; would be nice to clean it up
-        let minutes_remaining_1 = self.minute_limit - action_stack_1.iter().map(|(_, action_1)| action_1.minute_cost).sum::<u32>() as i32;
-        let minutes_remaining_2 = self.minute_limit - action_stack_2.iter().map(|(_, action_2)| action_2.minute_cost).sum::<u32>() as i32;
-        let mut end_1 = true;
-        let mut end_2 = true;
+/// The input has more valves worth opening than the solver was allowed to take on; it keeps a
+/// table entry for every subset of them, so each extra valve doubles the time and memory.
+#[derive(Debug)]
+struct TooManyValves {
+    valves: usize,
+    limit: usize,
+}
 
-        let mut visited: Vec<String> = Vec::new();
-        visited.extend(action_stack_1.iter().map(|(name, _)| name.clone()));
-        visited.extend(action_stack_2.iter().map(|(name, _)| name.clone()));
+impl fmt::Display for TooManyValves {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} valves have a flow rate, but the solver is limited to {}", self.valves, self.limit)
+    }
+}
 
-        for (name_1, candidate_1) in self.significant_nodes.iter() {
-            if visited.contains(name_1) {
-                continue;
-            }
+#[derive(Clone)]
+struct Plan {
+    released: u32,
+    /// (valve, minute opened) in the order they're opened
+    openings: Vec<(usize, u32)>,
+}
 
-            if let Some(action_1) = Action::consider(Rc::clone(&from_1), Rc::clone(candidate_1), minutes_remaining_1) {
-                end_1 = false;
-                action_stack_1.push((name_1.clone(), action_1));
-                visited.push(name_1.clone());
+/// Someone moving through the tunnels and opening valves, starting at `start` with `minutes` to spend.
+struct Agent {
+    start: String,
+    minutes: u32,
+}
 
-                for (name_2, candidate_2) in self.significant_nodes.iter() {
-                    if visited.contains(name_2) {
-                        continue;
-                    }
+impl Agent {
+    fn new(start: &str, minutes: u32) -> Agent {
+        Agent { start: start.to_string(), minutes }
+    }
+}
 
-                    if let Some(action_2) = Action::consider(Rc::clone(&from_2), Rc::clone(candidate_2), minutes_remaining_2) {
-                        end_2 = false;
-                        action_stack_2.push((name_2.clone(), action_2));
+struct Opening {
+    valve: String,
+    flow: u32,
+    /// the minute spent opening the valve; it releases pressure from the following minute
+    minute: u32,
+}
 
-                        self.find_next_optimal_move_with_elephant(Rc::clone(candidate_1), Rc::clone(candidate_2), action_stack_1, action_stack_2, max);
+enum Action {
+    Move(String),
+    Open(String),
+}
 
-                        action_stack_2.pop();
-                    }
-                }
+/// What one agent does in the optimal solution.
+struct AgentPlan {
+    start: String,
+    minutes: u32,
+    openings: Vec<Opening>,
+    /// the agent's action during each minute, from minute 1; it idles once these run out
+    actions: Vec<Action>,
+}
 
-                if end_2 {
-                    // allow 1 to continue even when 2 doesn't
-                    action_stack_2.push((action_stack_2.last().unwrap().0.clone(), Action::noop()));
+struct Solution {
+    released: u32,
+    /// one per agent, in the order the agents were given
+    plans: Vec<AgentPlan>,
+}
 
-                    self.find_next_optimal_move_with_elephant(Rc::clone(candidate_1), Rc::clone(&from_2), action_stack_1, action_stack_2, max);
+impl Solution {
+    fn minutes(&self) -> u32 {
+        self.plans.iter().map(|plan| plan.minutes).max().unwrap_or(0)
+    }
 
-                    action_stack_2.pop();
-                }
+    /// The valves releasing pressure during `minute`, in the order they were opened.
+    fn open_during(&self, minute: u32) -> Vec<&Opening> {
+        let mut open: Vec<&Opening> = self.plans.iter()
+            .filter(|plan| minute <= plan.minutes)
+            .flat_map(|plan| plan.openings.iter().filter(|opening| opening.minute < minute))
+            .collect();
+        open.sort_by_key(|opening| opening.minute);
+        open
+    }
 
-                visited.pop();
-                action_stack_1.pop();
-            }
-        }
+    /// The pressure released during each minute from minute 1, with the running total after it.
+    fn pressure_by_minute(&self) -> Vec<(u32, u32)> {
+        let mut total = 0;
 
-        if end_1 {
-            // allow 2 to continue even when 1 doesn't
-            action_stack_1.push((action_stack_1.last().unwrap().0.clone(), Action::noop()));
+        (1..=self.minutes()).map(|minute| {
+            let rate = self.open_during(minute).iter().map(|opening| opening.flow).sum();
+            total += rate;
+            (rate, total)
+        }).collect()
+    }
 
-            // 1 didn't go anywhere, so 2 had no chance to
-            // iterate; if 2 goes anywhere, push an Action::noop() onto stack 1 and recurse
-            for (name_2, candidate_2) in self.significant_nodes.iter() {
-                if visited.contains(name_2) {
-                    continue;
-                }
+    fn print_schedules(&self) {
+        for (i, plan) in self.plans.iter().enumerate() {
+            let steps: Vec<String> = plan.openings.iter().map(|opening| format!("{} at {}", opening.valve, opening.minute)).collect();
+            println!("agent {} (from {}, {} minutes): {}", i + 1, plan.start, plan.minutes, steps.join(", "));
+        }
+    }
+}
 
-                if let Some(action_2) = Action::consider(Rc::clone(&from_2), Rc::clone(candidate_2), minutes_remaining_2) {
-                    end_2 = false;
-                    action_stack_2.push((name_2.clone(), action_2));
+impl fmt::Display for Solution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (minute, (rate, total)) in (1..).zip(self.pressure_by_minute()) {
+            writeln!(f, "== Minute {minute} ==")?;
 
-                    self.find_next_optimal_move_with_elephant(Rc::clone(&from_1), Rc::clone(candidate_2), action_stack_1, action_stack_2, max);
+            let open: Vec<String> = self.open_during(minute).iter().map(|opening| format!("{} ({})", opening.valve, opening.flow)).collect();
+            if open.is_empty() {
+                writeln!(f, "No valves are open.")?;
+            } else {
+                writeln!(f, "Valves {} are open, releasing {rate} pressure ({total} in total).", open.join(", "))?;
+            }
 
-                    action_stack_2.pop();
+            for (i, plan) in self.plans.iter().enumerate() {
+                match plan.actions.get(minute as usize - 1) {
+                    Some(Action::Move(valve)) => writeln!(f, "Agent {} moves to valve {valve}.", i + 1)?,
+                    Some(Action::Open(valve)) => writeln!(f, "Agent {} opens valve {valve}.", i + 1)?,
+                    None => {}
                 }
             }
 
-            action_stack_1.pop();
+            writeln!(f)?;
         }
 
-        if end_1 && end_2 {
-            // when nobody can make any more progress, record our result
-            let total_released = action_stack_1.iter().map(|(_, action)| action.pressure_released).sum::<i32>()
-                + action_stack_2.iter().map(|(_, action)| action.pressure_released).sum::<i32>();
-
-            max.set(Some(max.get().map_or(total_released, |former_max| former_max.max(total_released))));
-        }
+        Ok(())
     }
 }
 
 const STARTING_VALVE: &'static str = "AA";
-const STARTING_MINUTES: i32 = 30;
+const STARTING_MINUTES: u32 = 30;
+const ELEPHANT_TRAINING_MINUTES: u32 = 4;
+/// the most valves with a flow rate the solver takes on; puzzle inputs have around 15
+const MAX_WORKING_VALVES: usize = 20;
 
 fn parse_valves(input: &String) -> Graph {
     lazy_static! {
-        static ref VALVE_REGEX: Regex = Regex::new(r"Valve (\w+) has flow rate=([\d-]+); tunnels? leads? to valves? ([\w, ]+)").unwrap();
+        static ref VALVE_REGEX: Regex = Regex::new(r"Valve (\w+) has flow rate=(\d+); tunnels? leads? to valves? ([\w, ]+)").unwrap();
     }
 
-    let mut valves = HashMap::new();
-    let mut connections: HashMap<String, Vec<String>> = HashMap::new();
+    let valves = input.lines().map(|line| {
+        let cap = VALVE_REGEX.captures(line).unwrap_or_else(|| panic!("couldn't parse valve: {line}"));
 
-    for line in input.lines() {
-        let cap = VALVE_REGEX.captures(line).unwrap();
-        let name = cap[1].to_string();
-
-        connections.insert(name.clone(), cap[3].split(", ").map(|s| s.to_string()).collect());
-        valves.insert(name.clone(), Rc::new(RefCell::new(Valve {
-            name,
+        Valve {
+            name: cap[1].to_string(),
             flow: cap[2].parse().unwrap(),
-            connected: HashMap::new(),
-            cached_paths: Box::new(RefCell::new(HashMap::new()))
-        })));
-    }
-
-    for (name, valve) in valves.iter() {
-        for connected in connections.get(name).unwrap().iter() {
-            valve.borrow_mut().connected.insert(connected.clone(), Rc::clone(valves.get(connected).unwrap()));
+            tunnels: cap[3].split(", ").map(|s| s.to_string()).collect(),
         }
-    }
+    }).collect();
 
-    // note: any fully orphaned SharedValue nodes will be dropped as we filter valves into only values with flow > 0
-    Graph {
-        start: Rc::clone(valves.get(STARTING_VALVE).unwrap()),
-        significant_nodes: valves.iter()
-            .filter(|(_, sv)| sv.borrow().flow > 0)
-            .map(|(k, v)| (k.clone(), Rc::clone(v))).collect(),
-        minute_limit: STARTING_MINUTES
-    }
+    Graph::new(valves)
 }
 
-#[aoc(day=16, part=1)]
+#[aoc(day=16, part=1, title = "Proboscidea Volcanium")]
 fn part1(input: String) -> String {
     let graph = parse_valves(&input);
+    let agents = [Agent::new(STARTING_VALVE, STARTING_MINUTES)];
+    let solution = graph.solve(&agents, MAX_WORKING_VALVES).unwrap_or_else(|e| panic!("can't solve this input: {e}"));
 
-    match graph.find_optimal_moves() {
-        Some(pressure_released) => pressure_released.to_string(),
-        None => "no valid paths found".to_string()
-    }
+    solution.print_schedules();
+    println!("{solution}");
+    solution.released.to_string()
 }
 
 #[aoc(day=16, part=2)]
 fn part2(input: String) -> String {
-    let mut graph = parse_valves(&input);
-
-    // elephant tax
-    graph.minute_limit -= 4;
-
-    match graph.find_optimal_moves_with_elephant() {
-        Some(pressure_released) => pressure_released.to_string(),
-        None => "no valid paths found".to_string()
-    }
+    let graph = parse_valves(&input);
+    // you and the elephant, once you've spent the time teaching it
+    let agents = [
+        Agent::new(STARTING_VALVE, STARTING_MINUTES - ELEPHANT_TRAINING_MINUTES),
+        Agent::new(STARTING_VALVE, STARTING_MINUTES - ELEPHANT_TRAINING_MINUTES),
+    ];
+    let solution = graph.solve(&agents, MAX_WORKING_VALVES).unwrap_or_else(|e| panic!("can't solve this input: {e}"));
+
+    solution.print_schedules();
+    println!("{solution}");
+    solution.released.to_string()
 }
@@ -1,75 +1,167 @@
 use std::cmp::max;
-use std::collections::{HashMap, HashSet};
+use std::fmt;
 use std::iter::from_fn;
+use std::rc::Rc;
+use aoclib::{cycle, parse};
 
 type CoordinateType = i64;
 type Coord = [CoordinateType; 2];
-type RockDef = &'static [&'static [bool]];
-
-const ROCK_DEFINITIONS: [RockDef; 5] = [
-    &[
-        &[true, true, true, true]
-    ],
-    &[
-        &[false, true, false],
-        &[ true, true,  true],
-        &[false, true, false]
-    ],
-    &[
-        &[false, false, true],
-        &[false, false, true],
-        &[ true,  true, true],
-    ],
-    &[
-        &[true],
-        &[true],
-        &[true],
-        &[true],
-    ],
-    &[
-        &[true, true],
-        &[true, true],
-    ],
-];
 
-struct Rock {
-    def: RockDef,
-    pos: Coord,
+/// One row of the chamber, with bit `x` set when column `x` is occupied.
+type Row = u64;
+
+/// The rocks from the puzzle, in the order they fall.
+const STANDARD_ROCKS: &str = include_str!("rocks.txt");
+
+#[derive(Debug)]
+enum ConfigError {
+    NoShapes,
+    InvalidCell { shape: usize, cell: char },
+    EmptyShape { shape: usize },
+    /// the chamber is wider than a `Row` can hold
+    TooWide { width: CoordinateType },
+    /// the shape is wider than any chamber can be
+    ShapeTooWide { shape: usize, width: CoordinateType },
+    Unreadable { path: String, message: String },
+    /// the shape can't spawn without hitting the right wall
+    ShapeDoesNotFit { shape: usize, width: CoordinateType },
 }
 
-impl Rock {
-    const NO_DELTA: Coord = [0, 0];
-
-    fn fits(&self, chamber: &Chamber) -> bool {
-        self.fits_with_delta(chamber, &Self::NO_DELTA)
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoShapes => write!(f, "no rock shapes were given"),
+            Self::InvalidCell { shape, cell } => write!(f, "rock shape {} contains {cell:?}; only '#' and '.' are allowed", shape + 1),
+            Self::EmptyShape { shape } => write!(f, "rock shape {} has no rock in it", shape + 1),
+            Self::TooWide { width } => write!(f, "chamber width {width} is not between 1 and {}", Row::BITS),
+            Self::ShapeTooWide { shape, width } => write!(f, "rock shape {} is {width} wide, but chambers are at most {} wide", shape + 1, Row::BITS),
+            Self::Unreadable { path, message } => write!(f, "couldn't read rock shapes from {path}: {message}"),
+            Self::ShapeDoesNotFit { shape, width } => write!(f, "rock shape {} is {width} wide and doesn't fit at the spawn position", shape + 1),
+        }
     }
+}
 
-    fn fits_with_delta(&self, chamber: &Chamber, delta: &Coord) -> bool {
-        for (y, row) in self.def.iter().enumerate() {
-            for (x, cell_state) in row.iter().enumerate() {
-                if !cell_state {
-                    continue;
-                }
+/// A rock as row masks from the bottom up, aligned to the left wall.
+struct RockShape {
+    rows: Vec<Row>,
+    width: CoordinateType,
+}
 
-                if chamber.is_blocked(&[self.pos[0] + delta[0] + x as CoordinateType, self.pos[1] + delta[1] - y as CoordinateType]) {
-                    return false;
+impl RockShape {
+    /// Parses shapes drawn with `#` for rock and `.` for air, separated by blank lines.
+    fn parse_all(art: &str) -> Result<Vec<RockShape>, ConfigError> {
+        parse::paragraphs(art).enumerate().map(|(shape, block)| {
+            let mut rows = Vec::new();
+            for line in block.lines().rev() {
+                let mut mask: Row = 0;
+                for (x, cell) in line.trim_end().chars().enumerate() {
+                    match cell {
+                        '#' if x < Row::BITS as usize => mask |= 1 << x,
+                        '#' => return Err(ConfigError::ShapeTooWide { shape, width: x as CoordinateType + 1 }),
+                        '.' => {}
+                        _ => return Err(ConfigError::InvalidCell { shape, cell }),
+                    }
                 }
+                rows.push(mask);
+            }
+
+            let combined = rows.iter().fold(0, |all, &row| all | row);
+            if combined == 0 {
+                return Err(ConfigError::EmptyShape { shape });
             }
+
+            Ok(RockShape { rows, width: (Row::BITS - combined.leading_zeros()) as CoordinateType })
+        }).collect()
+    }
+
+    /// Reads shapes drawn as for [`RockShape::parse_all`] from a file.
+    fn load(path: &str) -> Result<Vec<RockShape>, ConfigError> {
+        let art = std::fs::read_to_string(path).map_err(|e| ConfigError::Unreadable { path: path.to_string(), message: e.to_string() })?;
+        RockShape::parse_all(&art)
+    }
+}
+
+/// The rules of the simulation: how wide the chamber is, which rocks fall in what order and
+/// where each one appears.
+struct ChamberConfig {
+    width: CoordinateType,
+    /// the gap between the left wall and a new rock
+    spawn_x: CoordinateType,
+    /// the gap between the highest rock (or the floor) and a new rock
+    spawn_dy: CoordinateType,
+    shapes: Vec<Rc<RockShape>>,
+}
+
+impl ChamberConfig {
+    fn new(width: CoordinateType, spawn_x: CoordinateType, spawn_dy: CoordinateType, shapes: Vec<RockShape>) -> Result<ChamberConfig, ConfigError> {
+        if width < 1 || width > Row::BITS as CoordinateType {
+            return Err(ConfigError::TooWide { width });
         }
 
-        true
+        if shapes.is_empty() {
+            return Err(ConfigError::NoShapes);
+        }
+
+        if let Some((shape, rock)) = shapes.iter().enumerate().find(|(_, rock)| spawn_x + rock.width > width) {
+            return Err(ConfigError::ShapeDoesNotFit { shape, width: rock.width });
+        }
+
+        Ok(ChamberConfig { width, spawn_x, spawn_dy, shapes: shapes.into_iter().map(Rc::new).collect() })
+    }
+
+    fn standard() -> ChamberConfig {
+        ChamberConfig::new(7, 2, 3, RockShape::parse_all(STANDARD_ROCKS).unwrap()).unwrap()
+    }
+
+    /// The puzzle's chamber, but with the rocks drawn in the file named by `AOC_ROCK_SHAPES`
+    /// when that's set.
+    fn from_env() -> Result<ChamberConfig, ConfigError> {
+        match std::env::var("AOC_ROCK_SHAPES") {
+            Ok(path) => ChamberConfig::new(7, 2, 3, RockShape::load(&path)?),
+            Err(_) => Ok(ChamberConfig::standard()),
+        }
+    }
+
+    fn rock_shape_iter(&self) -> impl Iterator<Item = (usize, Rc<RockShape>)> {
+        let shapes = self.shapes.clone();
+        let mut i = 0;
+
+        from_fn(move || {
+            let next = Some((i, Rc::clone(&shapes[i])));
+
+            i = (i + 1) % shapes.len();
+
+            next
+        })
+    }
+}
+
+struct Rock {
+    shape: Rc<RockShape>,
+    /// the bottom-left corner of the rock's bounding box
+    pos: Coord,
+}
+
+impl Rock {
+    fn fits(&self, chamber: &Chamber) -> bool {
+        !chamber.collides(&self.shape, self.pos)
     }
 
     fn move_if_fits(&mut self, chamber: &Chamber, delta: &Coord) -> bool {
-        if !self.fits_with_delta(chamber, delta) {
+        let moved = [self.pos[0] + delta[0], self.pos[1] + delta[1]];
+        if chamber.collides(&self.shape, moved) {
             return false;
         }
 
-        self.pos[0] += delta[0];
-        self.pos[1] += delta[1];
+        self.pos = moved;
 
         true
     }
+
+    fn covers(&self, x: CoordinateType, y: CoordinateType) -> bool {
+        let (dx, dy) = (x - self.pos[0], y - self.pos[1]);
+        dx >= 0 && dy >= 0 && self.shape.rows.get(dy as usize).is_some_and(|&mask| mask >> dx & 1 != 0)
+    }
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -90,145 +182,283 @@ impl Movement {
 }
 
 struct Chamber {
-    grid: HashSet<Coord>,
+    width: CoordinateType,
+    spawn_x: CoordinateType,
+    spawn_dy: CoordinateType,
+    /// settled rock, from the lowest row that's still kept
+    rows: Vec<Row>,
+    /// how many rows below `rows[0]` have been discarded as unreachable
+    truncated: CoordinateType,
+    /// the number of kept rows at which to next try discarding unreachable ones
+    next_truncation: usize,
     max_y: CoordinateType,
 }
 
 impl Chamber {
-    const WIDTH: CoordinateType = 7;
-    const SPAWN_X: CoordinateType = 2;
-    const SPAWN_DY: CoordinateType = 3;
+    const TRUNCATION_THRESHOLD: usize = 256;
 
+    /// Whether a rock at `pos` would overlap a wall, the floor or settled rock.
     #[inline]
-    fn is_blocked(&self, at: &Coord) -> bool {
-        at[0] < 0 || at[1] < 0 || at[0] >= Self::WIDTH || self.grid.contains(at)
+    fn collides(&self, shape: &RockShape, pos: Coord) -> bool {
+        if pos[0] < 0 || pos[0] + shape.width > self.width || pos[1] < self.truncated {
+            return true;
+        }
+
+        let bottom = (pos[1] - self.truncated) as usize;
+        shape.rows.iter().enumerate().any(|(dy, &mask)| {
+            self.rows.get(bottom + dy).is_some_and(|&row| row & mask << pos[0] != 0)
+        })
     }
 
     #[inline]
-    fn spawn_rock(&self, def: RockDef) -> Rock {
+    fn spawn_rock(&self, shape: Rc<RockShape>) -> Rock {
         Rock {
-            def,
-            pos: [Self::SPAWN_X, self.max_y + Self::SPAWN_DY + def.len() as CoordinateType],
+            shape,
+            pos: [self.spawn_x, self.max_y + self.spawn_dy + 1],
         }
     }
 
     fn insert(&mut self, rock: &Rock) {
-        for (y, row) in rock.def.iter().enumerate() {
-            for (x, cell_state) in row.iter().enumerate() {
-                if !cell_state {
-                    continue;
-                }
+        let bottom = (rock.pos[1] - self.truncated) as usize;
+        let top = bottom + rock.shape.rows.len();
+        if self.rows.len() < top {
+            self.rows.resize(top, 0);
+        }
 
-                self.grid.insert([rock.pos[0] + x as CoordinateType, rock.pos[1] - y as CoordinateType]);
+        for (dy, &mask) in rock.shape.rows.iter().enumerate() {
+            let mask = mask << rock.pos[0];
+            self.rows[bottom + dy] |= mask;
+
+            if mask != 0 {
+                self.max_y = max(self.max_y, rock.pos[1] + dy as CoordinateType);
             }
         }
 
-        self.max_y = max(self.max_y,rock.pos[1]);
+        if self.rows.len() >= self.next_truncation {
+            self.truncate();
+        }
     }
 
-    fn simulate_rocks(&mut self, count: u64, rocks: &mut impl Iterator<Item = (usize, RockDef)>, jets: &mut impl Iterator<Item = (usize, Movement)>) {
-        for _ in 0..count {
-            let mut rock = self.spawn_rock(rocks.next().unwrap().1);
+    /// The empty cells a falling rock could still get to, found by flooding the empty space left,
+    /// right and down from above the tower. Returns a mask of them for each kept row, plus the
+    /// lowest row reached.
+    fn reachable(&self) -> (Vec<Row>, usize) {
+        // the empty row just above the tower is where the flood starts
+        let width = self.width as usize;
+        let mut reached: Vec<Row> = vec![0; self.rows.len() + 1];
+        let mut stack: Vec<(usize, usize)> = (0..width).map(|x| (x, self.rows.len())).collect();
+        let mut lowest = self.rows.len();
+
+        while let Some((x, y)) = stack.pop() {
+            let blocked = self.rows.get(y).is_some_and(|&row| row & 1 << x != 0);
+            if blocked || reached[y] & 1 << x != 0 {
+                continue;
+            }
+            reached[y] |= 1 << x;
 
-            assert!(rock.fits(self));
+            lowest = lowest.min(y);
+            if x > 0 {
+                stack.push((x - 1, y));
+            }
+            if x + 1 < width {
+                stack.push((x + 1, y));
+            }
+            if y > 0 {
+                stack.push((x, y - 1));
+            }
+        }
 
-            loop {
-                rock.move_if_fits(self, &jets.next().unwrap().1.get_delta());
+        reached.truncate(self.rows.len());
+        (reached, lowest)
+    }
 
-                if !rock.move_if_fits(self, &Movement::DOWN) {
-                    break;
-                }
-            }
+    /// Discards rows no falling rock can reach any more.
+    fn truncate(&mut self) {
+        let (_, lowest) = self.reachable();
 
-            self.insert(&rock);
-        }
+        self.rows.drain(..lowest);
+        self.truncated += lowest as CoordinateType;
+        self.next_truncation = max(Self::TRUNCATION_THRESHOLD, self.rows.len() * 2);
     }
 
-    fn find_pattern_and_guess(&mut self, count: u64, rocks: &mut impl Iterator<Item = (usize, RockDef)>, jets: &mut impl Iterator<Item = (usize, Movement)>) -> CoordinateType {
-        let mut starting_combinations: HashMap<(usize, usize), (u64, CoordinateType)> = HashMap::new();
-        let mut last_pattern: Option<(u64, CoordinateType)> = None;
-        let mut consecutive_pattern = 0;
+    /// The top of the tower as far down as a falling rock could reach, up to the highest rock,
+    /// with every cell no rock can get to filled in. Rocks spawn relative to the highest rock, so
+    /// this and the next rock and jet are everything that decides how the tower grows from here.
+    fn surface(&self) -> Vec<Row> {
+        let (reached, lowest) = self.reachable();
+        let full = Row::MAX >> (Row::BITS - self.width as u32);
+        let top = (self.max_y + 1 - self.truncated) as usize;
 
-        for li in 0..count {
-            let (ri, rock_def) = rocks.next().unwrap();
-            let mut check_for_pattern = true;
-            let mut rock = self.spawn_rock(rock_def);
+        reached[lowest.min(top)..top].iter().map(|&row| full & !row).collect()
+    }
 
-            assert!(rock.fits(self));
+    fn is_settled(&self, x: CoordinateType, y: CoordinateType) -> bool {
+        y >= self.truncated && self.rows.get((y - self.truncated) as usize).is_some_and(|&row| row >> x & 1 != 0)
+    }
 
-            loop {
-                let (ji, movement) = jets.next().unwrap();
+    /// Draws up to `max_rows` rows from the top of the tower (or of the falling rock) down, with
+    /// settled rock as `#` and the falling rock as `@`.
+    fn render(&self, falling: Option<&Rock>, max_rows: usize) -> String {
+        let rock_top = falling.map_or(self.max_y, |rock| rock.pos[1] + rock.shape.rows.len() as CoordinateType - 1);
+        let top = max(self.max_y, rock_top);
+        let mut output = String::new();
+
+        for y in (self.truncated..=top).rev().take(max_rows) {
+            output.push('|');
+            for x in 0..self.width {
+                output.push(if falling.is_some_and(|rock| rock.covers(x, y)) {
+                    '@'
+                } else if self.is_settled(x, y) {
+                    '#'
+                } else {
+                    '.'
+                });
+            }
+            output.push_str("|\n");
+        }
 
-                if check_for_pattern {
-                    let combination = (ri, ji);
+        if top - self.truncated < max_rows as CoordinateType {
+            // the whole kept tower is on screen, so show what's beneath it
+            let edge = if self.truncated == 0 { '-' } else { '~' };
+            output.push('+');
+            output.extend((0..self.width).map(|_| edge));
+            output.push_str("+\n");
+        }
 
-                    if let Some((former_li, former_height)) = starting_combinations.get(&combination) {
-                        let current_height = self.max_y + 1;
-                        let li_delta = li - former_li;
-                        let height_delta = current_height - former_height;
+        output
+    }
 
-                        match last_pattern {
-                            Some((li_pattern, height_pattern)) if li_pattern == li_delta && height_pattern == height_delta => {
-                                consecutive_pattern += 1;
+    /// Drops a single rock until it settles, returning the rock and jet indices it started with.
+    fn drop_rock(&mut self, rocks: &mut impl Iterator<Item = (usize, Rc<RockShape>)>, jets: &mut impl Iterator<Item = (usize, Movement)>) -> (usize, usize) {
+        let (ri, shape) = rocks.next().unwrap();
+        let mut rock = self.spawn_rock(shape);
+        let mut first_ji = None;
 
-                                if consecutive_pattern == li_pattern {
-                                    println!("Confirmed pattern +{li_pattern} indices, +{height_pattern} height at index {li}");
-                                    println!("Estimated return at index {}, if pattern continues to hold",
-                                             li / li_pattern * li_pattern + count % li_pattern);
-                                }
+        assert!(rock.fits(self));
 
-                                if consecutive_pattern >= li_pattern && (count - li) % li_pattern == 0 {
-                                    let guess = current_height + (count - li) as CoordinateType / li_pattern as CoordinateType * height_pattern;
+        loop {
+            let (ji, movement) = jets.next().unwrap();
+            first_ji.get_or_insert(ji);
 
-                                    println!("Pattern implies height will be {guess}; returning early");
+            rock.move_if_fits(self, &movement.get_delta());
 
-                                    return guess;
-                                }
-                            },
-                            _ => {
-                                consecutive_pattern = 0;
-                                last_pattern = Some((li_delta, height_delta));
-                            }
-                        }
-                    }
+            if !rock.move_if_fits(self, &Movement::DOWN) {
+                break;
+            }
+        }
 
-                    starting_combinations.insert(combination, (li, self.max_y + 1));
-                    check_for_pattern = false;
-                }
+        self.insert(&rock);
 
-                rock.move_if_fits(self, &movement.get_delta());
+        (ri, first_ji.unwrap())
+    }
 
-                if !rock.move_if_fits(self, &Movement::DOWN) {
-                    break;
-                }
-            }
+    fn simulate_rocks(&mut self, count: u64, rocks: &mut impl Iterator<Item = (usize, Rc<RockShape>)>, jets: &mut impl Iterator<Item = (usize, Movement)>) {
+        for _ in 0..count {
+            self.drop_rock(rocks, jets);
+        }
+    }
+
+    fn find_pattern_and_guess(&mut self, count: u64, rocks: &mut impl Iterator<Item = (usize, Rc<RockShape>)>, jets: &mut impl Iterator<Item = (usize, Movement)>) -> CoordinateType {
+        // the next rock and jet plus the reachable surface are the whole state, so the first repeat is a cycle
+        let mut rocks = rocks.peekable();
+        let mut jets = jets.peekable();
+        let guess = cycle::extrapolate(count, 0, 0, || {
+            let state = (rocks.peek().unwrap().0, jets.peek().unwrap().0, self.surface());
+            self.drop_rock(&mut rocks, &mut jets);
 
-            self.insert(&rock);
+            (state, self.max_y + 1)
+        });
+
+        if let Some(pattern) = guess.cycle {
+            println!("Confirmed pattern +{} indices starting at index {}", pattern.length, pattern.start);
+            println!("Pattern implies height will be {}", guess.value);
         }
 
-        self.max_y + 1
+        guess.value
     }
 
-    fn new() -> Chamber {
+    fn new(config: &ChamberConfig) -> Chamber {
         Chamber {
-            grid: HashSet::new(),
-            max_y: -1
+            width: config.width,
+            spawn_x: config.spawn_x,
+            spawn_dy: config.spawn_dy,
+            rows: Vec::new(),
+            truncated: 0,
+            next_truncation: Chamber::TRUNCATION_THRESHOLD,
+            max_y: -1,
         }
     }
 }
 
-fn rock_def_iter() -> impl Iterator<Item = (usize, RockDef)> {
-    let mut i = 0;
+enum StepEvent {
+    Spawned { rock: usize },
+    Pushed { jet: usize, movement: Movement, moved: bool },
+    Fell,
+    Settled,
+}
 
-    from_fn(move || {
-        let next = Some((i, ROCK_DEFINITIONS[i]));
+/// Runs the simulation one jet push or one drop at a time, for watching it unfold.
+struct Replay<R, J> {
+    chamber: Chamber,
+    rocks: R,
+    jets: J,
+    falling: Option<Rock>,
+    /// whether the falling rock is pushed by a jet next, rather than falling
+    push_next: bool,
+    settled: u64,
+}
 
-        i = (i + 1) % ROCK_DEFINITIONS.len();
+impl<R: Iterator<Item = (usize, Rc<RockShape>)>, J: Iterator<Item = (usize, Movement)>> Replay<R, J> {
+    fn new(config: &ChamberConfig, rocks: R, jets: J) -> Self {
+        Replay { chamber: Chamber::new(config), rocks, jets, falling: None, push_next: true, settled: 0 }
+    }
 
-        next
-    })
+    fn step(&mut self) -> StepEvent {
+        let Some(rock) = &mut self.falling else {
+            let (ri, shape) = self.rocks.next().unwrap();
+            self.falling = Some(self.chamber.spawn_rock(shape));
+            self.push_next = true;
+            return StepEvent::Spawned { rock: ri };
+        };
+
+        if self.push_next {
+            let (ji, movement) = self.jets.next().unwrap();
+            let moved = rock.move_if_fits(&self.chamber, &movement.get_delta());
+            self.push_next = false;
+            return StepEvent::Pushed { jet: ji, movement, moved };
+        }
+
+        self.push_next = true;
+        if rock.move_if_fits(&self.chamber, &Movement::DOWN) {
+            return StepEvent::Fell;
+        }
+
+        self.chamber.insert(rock);
+        self.falling = None;
+        self.settled += 1;
+        StepEvent::Settled
+    }
+
+    fn describe(&self, event: &StepEvent) -> String {
+        let rock = self.settled + self.falling.is_some() as u64;
+        let action = match event {
+            StepEvent::Spawned { rock } => format!("shape {rock} appears"),
+            StepEvent::Pushed { jet, movement, moved: true } => format!("jet {jet} pushes it {movement:?}"),
+            StepEvent::Pushed { jet, movement, moved: false } => format!("jet {jet} pushes it {movement:?}, but nothing happens"),
+            StepEvent::Fell => "it falls 1 unit".to_string(),
+            StepEvent::Settled => "it comes to rest".to_string(),
+        };
+
+        format!("rock {rock}: {action} (tower height {})", self.chamber.max_y + 1)
+    }
+
+    fn render(&self, max_rows: usize) -> String {
+        self.chamber.render(self.falling.as_ref(), max_rows)
+    }
 }
 
+const REPLAY_VIEW_ROWS: usize = 24;
+
 fn jet_movement_iter(input: String) -> impl Iterator<Item = (usize, Movement)> {
     let jets: Vec<Movement> = input.chars().map(|c| match c {
         '<' => Movement::Left,
@@ -246,14 +476,19 @@ fn jet_movement_iter(input: String) -> impl Iterator<Item = (usize, Movement)> {
     })
 }
 
-#[aoc(day=17, part=1)]
+fn chamber_config() -> ChamberConfig {
+    ChamberConfig::from_env().unwrap_or_else(|e| panic!("invalid chamber configuration: {e}"))
+}
+
+#[aoc(day=17, part=1, title = "Pyroclastic Flow")]
 fn part1(input: String) -> String {
     const GOAL: u64 = 2022;
 
-    let mut chamber = Chamber::new();
+    let config = chamber_config();
+    let mut chamber = Chamber::new(&config);
 
     // chamber.find_pattern_and_guess from part 2 would also be sufficient for this
-    chamber.simulate_rocks(GOAL, &mut rock_def_iter(), &mut jet_movement_iter(input));
+    chamber.simulate_rocks(GOAL, &mut config.rock_shape_iter(), &mut jet_movement_iter(input));
 
     (chamber.max_y + 1).to_string()
 }
@@ -262,7 +497,90 @@ fn part1(input: String) -> String {
 fn part2(input: String) -> String {
     const GOAL: u64 = 1_000_000_000_000;
 
-    Chamber::new()
-        .find_pattern_and_guess(GOAL, &mut rock_def_iter(), &mut jet_movement_iter(input))
+    let config = chamber_config();
+
+    Chamber::new(&config)
+        .find_pattern_and_guess(GOAL, &mut config.rock_shape_iter(), &mut jet_movement_iter(input))
         .to_string()
 }
+
+/// Cross-checks the cycle extrapolation against brute-force simulation for counts small enough
+/// to simulate, with the puzzle's chamber and with a few other widths and shapes.
+#[aoc(day=17, part=2, variant = "verify")]
+fn part2_verify(input: String) -> String {
+    const COUNTS: [u64; 6] = [1, 5, 100, 2022, 5_000, 12_345];
+
+    let alt_rocks = || RockShape::parse_all(include_str!("alt_rocks.txt")).unwrap();
+    let configs = [
+        ("standard", ChamberConfig::standard()),
+        ("wide", ChamberConfig::new(8, 3, 3, RockShape::parse_all(STANDARD_ROCKS).unwrap()).unwrap()),
+        ("very wide", ChamberConfig::new(12, 3, 3, RockShape::parse_all(STANDARD_ROCKS).unwrap()).unwrap()),
+        ("narrow", ChamberConfig::new(5, 1, 2, alt_rocks()).unwrap()),
+        ("alternate", ChamberConfig::new(7, 2, 3, alt_rocks()).unwrap()),
+    ];
+
+    for (name, config) in configs.iter() {
+        for count in COUNTS {
+            let guessed = Chamber::new(config).find_pattern_and_guess(count, &mut config.rock_shape_iter(), &mut jet_movement_iter(input.clone()));
+
+            let mut chamber = Chamber::new(config);
+            chamber.simulate_rocks(count, &mut config.rock_shape_iter(), &mut jet_movement_iter(input.clone()));
+            let simulated = chamber.max_y + 1;
+
+            println!("{name} chamber, {count} rocks: extrapolated {guessed}, simulated {simulated}");
+            assert_eq!(guessed, simulated, "extrapolation is wrong for {count} rocks in the {name} chamber");
+        }
+    }
+
+    format!("extrapolation matches simulation for {} counts in {} chambers", COUNTS.len(), configs.len())
+}
+
+/// Steps through the simulation on the terminal: Enter advances one jet push or drop, a number
+/// advances that many steps, and `q` (or the end of stdin) stops.
+#[aoc(day=17, part=1, variant = "step")]
+fn part1_step(input: String) -> String {
+    let config = chamber_config();
+    let mut replay = Replay::new(&config, config.rock_shape_iter(), jet_movement_iter(input));
+    let mut line = String::new();
+
+    loop {
+        println!("{}", replay.render(REPLAY_VIEW_ROWS));
+
+        line.clear();
+        if std::io::stdin().read_line(&mut line).unwrap_or(0) == 0 || line.trim() == "q" {
+            break;
+        }
+
+        for _ in 0..line.trim().parse::<u32>().unwrap_or(1) {
+            let event = replay.step();
+            println!("{}", replay.describe(&event));
+        }
+    }
+
+    (replay.chamber.max_y + 1).to_string()
+}
+
+/// Writes a text file per step of the first few rocks, to the directory in `AOC_FRAME_DIR`
+/// (`day17-frames` by default).
+#[aoc(day=17, part=1, variant = "frames")]
+fn part1_frames(input: String) -> String {
+    const FRAME_ROCKS: u64 = 25;
+
+    let dir = std::env::var("AOC_FRAME_DIR").unwrap_or_else(|_| "day17-frames".to_string());
+    std::fs::create_dir_all(&dir).unwrap_or_else(|e| panic!("couldn't create {dir}: {e}"));
+
+    let config = chamber_config();
+    let mut replay = Replay::new(&config, config.rock_shape_iter(), jet_movement_iter(input));
+    let mut frame = 0;
+
+    while replay.settled < FRAME_ROCKS {
+        let event = replay.step();
+        frame += 1;
+
+        let path = format!("{dir}/frame-{frame:05}.txt");
+        let contents = format!("{}\n\n{}", replay.describe(&event), replay.render(REPLAY_VIEW_ROWS));
+        std::fs::write(&path, contents).unwrap_or_else(|e| panic!("couldn't write {path}: {e}"));
+    }
+
+    format!("wrote {frame} frames for {FRAME_ROCKS} rocks to {dir}")
+}
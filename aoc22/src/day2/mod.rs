@@ -109,7 +109,7 @@ impl GameRecord {
     }
 }
 
-#[aoc(day2, part1)]
+#[aoc(day2, part1, title = "Rock Paper Scissors")]
 pub fn part1(input: String) -> String {
     let lines = input.split("\n");
     let mut running_total = 0i32;
@@ -63,7 +63,7 @@ fn parse_sacks(input: &String) -> Vec<Sack> {
     input.split("\n").map(Sack::from).collect()
 }
 
-#[aoc(day3, part1)]
+#[aoc(day3, part1, title = "Rucksack Reorganization")]
 fn part1(input: String) -> String {
     let sacks = parse_sacks(&input);
     let mut total = 0i32;
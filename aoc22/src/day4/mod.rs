@@ -75,7 +75,7 @@ fn find_overlaps(input: &String, pred: &dyn Fn(&AssignedPair) -> bool) -> String
     overlaps.to_string()
 }
 
-#[aoc(day4, part1)]
+#[aoc(day4, part1, title = "Camp Cleanup")]
 fn part1(input: String) -> String {
     find_overlaps(&input, &AssignedPair::has_full_overlap)
 }
@@ -1,155 +1,260 @@
-use regex::Regex;
-use lazy_static::lazy_static;
-use std::collections::HashMap;
-
-struct SupplyStacks {
-    stacks: HashMap<i8, Box<Vec<char>>>,
-    /** (move qty, move from key, move to key) */
-    insn: Vec<(i8, i8, i8)>,
+use std::fmt;
+use aoclib::parse::{self, ScanError};
+
+#[derive(Debug)]
+enum ParseError {
+    /// the diagram has no row of stack labels under it
+    MissingLabels,
+    DuplicateLabel { label: String },
+    /// a crate drawn without its closing bracket, or holding something other than one visible character
+    InvalidCrate { line: usize, column: usize },
+    /// a crate that isn't drawn above any stack label
+    UnalignedCrate { line: usize, column: usize },
+    InvalidInstruction { line: usize, error: ScanError },
 }
 
-impl SupplyStacks {
-    fn process(&mut self) {
-        for (ref repeat, ref from, ref to) in &self.insn {
-            for _ in 0..*repeat {
-                let c = self.stacks.get_mut(from).unwrap().pop().unwrap().clone();
-                self.stacks.get_mut(to).unwrap().push(c);
-            }
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingLabels => write!(f, "the crate diagram has no stack labels"),
+            Self::DuplicateLabel { label } => write!(f, "stack {label} is labelled more than once"),
+            Self::InvalidCrate { line, column } => write!(f, "line {line}, column {column}: expected a crate like [A]"),
+            Self::UnalignedCrate { line, column } => write!(f, "line {line}, column {column}: crate is not above any stack label"),
+            Self::InvalidInstruction { line, error } => write!(f, "line {line}: {error}"),
         }
     }
+}
 
-    fn process_9001(&mut self) {
-        for (ref count, ref from, ref to) in &self.insn {
-            let src = self.stacks.get(from).unwrap();
-            let starting_at = (src.len() as i8 - count) as usize;
-            let moving: Vec<char> = (&src[starting_at..]).iter().map(|c| *c).collect();
+struct Instruction {
+    /// the line of the input this came from, for reporting
+    line: usize,
+    count: usize,
+    from: String,
+    to: String,
+}
 
-            let dest = self.stacks.get_mut(to).unwrap();
+struct SupplyStacks {
+    /// stack labels, left to right as drawn
+    labels: Vec<String>,
+    /// crates in each stack from the bottom up, in the same order as `labels`
+    stacks: Vec<Vec<char>>,
+    insn: Vec<Instruction>,
+}
 
-            for char in moving {
-                dest.push(char.clone());
-            }
+/// A crane model: how it takes `count` crates off the top of a stack.
+trait Crane {
+    fn name(&self) -> &str;
 
-            self.stacks.get_mut(from).unwrap().truncate(starting_at);
-        }
+    /// Removes the top `count` crates of `stack`, which holds at least that many, and returns them
+    /// in the order they end up stacked on the destination, bottom first.
+    fn lift(&self, stack: &mut Vec<char>, count: usize) -> Vec<char>;
+}
+
+/// Moves crates one at a time, so they land in reverse order.
+struct CrateMover9000;
+
+impl Crane for CrateMover9000 {
+    fn name(&self) -> &str {
+        "CrateMover 9000"
     }
 
-    fn pretty_print(&self) {
-        let mut sorted_keys: Vec<&i8> = self.stacks.keys().collect();
-        sorted_keys.sort();
+    fn lift(&self, stack: &mut Vec<char>, count: usize) -> Vec<char> {
+        let mut lifted = stack.split_off(stack.len() - count);
+        lifted.reverse();
+        lifted
+    }
+}
 
-        for key in sorted_keys {
-            print!("{key}:");
+/// Moves all the crates at once, keeping their order.
+struct CrateMover9001;
 
-            for c in self.stacks.get(key).unwrap().iter() {
-                print!(" {c}");
-            }
+impl Crane for CrateMover9001 {
+    fn name(&self) -> &str {
+        "CrateMover 9001"
+    }
 
-            println!();
-        }
+    fn lift(&self, stack: &mut Vec<char>, count: usize) -> Vec<char> {
+        stack.split_off(stack.len() - count)
     }
+}
 
-    fn peek_string(&self) -> String {
-        let mut chars: Vec<char> = Vec::new();
-        let mut sorted_keys: Vec<&i8> = self.stacks.keys().collect();
-        sorted_keys.sort();
+#[derive(Debug)]
+enum MoveError {
+    UnknownStack { line: usize, label: String },
+    NotEnoughCrates { line: usize, label: String, count: usize, available: usize },
+}
 
-        for key in sorted_keys {
-            chars.push(self.stacks.get(key).unwrap().last().unwrap().clone());
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownStack { line, label } => write!(f, "line {line}: no stack labelled {label}"),
+            Self::NotEnoughCrates { line, label, count, available } =>
+                write!(f, "line {line}: can't move {count} crates from stack {label}, which only holds {available}"),
         }
-
-        chars.iter().collect()
     }
 }
 
-fn parse_input(input: &String) -> SupplyStacks {
-    lazy_static! {
-        static ref CRATE_LABEL_REGEX: Regex = Regex::new(r"(\d)").unwrap();
-        static ref INSN_LIST_REGEX: Regex = Regex::new(r"move (\d+) from (\d+) to (\d+)").unwrap();
+impl SupplyStacks {
+    fn index_of(&self, label: &str, line: usize) -> Result<usize, MoveError> {
+        self.labels.iter().position(|l| l == label).ok_or_else(|| MoveError::UnknownStack { line, label: label.to_string() })
     }
 
-    let mut crates: Vec<&str> = Vec::new();
+    /// Carries out the `i`th instruction, leaving the stacks untouched if it's invalid.
+    fn step(&mut self, crane: &dyn Crane, i: usize) -> Result<(), MoveError> {
+        let insn = &self.insn[i];
+        let (from, to) = (self.index_of(&insn.from, insn.line)?, self.index_of(&insn.to, insn.line)?);
 
-    for line in input.lines() {
-        if line.is_empty() {
-            break;
+        let available = self.stacks[from].len();
+        if insn.count > available {
+            return Err(MoveError::NotEnoughCrates { line: insn.line, label: insn.from.clone(), count: insn.count, available });
         }
 
-        crates.push(line);
+        // putting crates back where they came from leaves the stack as it was, whatever the crane
+        if from == to {
+            return Ok(());
+        }
+
+        let lifted = crane.lift(&mut self.stacks[from], insn.count);
+        self.stacks[to].extend(lifted);
+        Ok(())
     }
 
-    let mut result = SupplyStacks {
-        stacks: HashMap::new(),
-        insn: Vec::new()
-    };
-    let labels = crates.pop().unwrap();
-    crates.reverse();
+    /// Carries out every instruction in order, stopping at the first invalid one. With `snapshots`,
+    /// prints the stacks after each step.
+    fn run(&mut self, crane: &dyn Crane, snapshots: bool) -> Result<(), MoveError> {
+        for i in 0..self.insn.len() {
+            self.step(crane, i)?;
+
+            if snapshots {
+                let insn = &self.insn[i];
+                println!("line {}: {} moves {} from {} to {}", insn.line, crane.name(), insn.count, insn.from, insn.to);
+                self.pretty_print();
+                println!();
+            }
+        }
 
-    println!("labels are {labels}");
+        Ok(())
+    }
 
-    for line in &crates {
-        println!("crates are {line}");
+    fn pretty_print(&self) {
+        for (label, stack) in self.labels.iter().zip(self.stacks.iter()) {
+            print!("{label}:");
+
+            for c in stack.iter() {
+                print!(" {c}");
+            }
+
+            println!();
+        }
     }
 
-    println!();
+    /// The top crate of each stack, with a space for an empty stack so later letters keep their place.
+    fn peek_string(&self) -> String {
+        self.stacks.iter().map(|stack| stack.last().copied().unwrap_or(' ')).collect()
+    }
+}
 
-    let mut idx = 0;
+/// Finds each `[X]` crate on a diagram line, returning the character columns of its brackets and its contents.
+fn parse_crate_row(line: &str, line_number: usize) -> Result<Vec<(usize, usize, char)>, ParseError> {
+    let mut crates = Vec::new();
+    let mut rest = line.chars().enumerate();
 
-    loop {
-        if !CRATE_LABEL_REGEX.is_match_at(labels, idx) {
-            break;
+    while let Some((start, c)) = rest.next() {
+        match c {
+            ' ' => continue,
+            '[' => {}
+            _ => return Err(ParseError::InvalidCrate { line: line_number, column: start + 1 }),
         }
 
-        let m = CRATE_LABEL_REGEX.find_at(labels, idx).unwrap();
-        let mut stack: Vec<char> = Vec::new();
+        match (rest.next(), rest.next()) {
+            (Some((_, label)), Some((end, ']'))) if label != ']' && !label.is_whitespace() => crates.push((start, end, label)),
+            _ => return Err(ParseError::InvalidCrate { line: line_number, column: start + 1 }),
+        }
+    }
 
-        println!("looking for stack {} at index {}", m.as_str(), m.start());
+    Ok(crates)
+}
 
-        // crates has been reversed; proceed in natural order to correctly assemble the stack
-        for line in crates.iter() {
-            if m.start() >= line.len() {
-                break;
-            }
+fn parse_input(input: &str) -> Result<SupplyStacks, ParseError> {
+    let lines: Vec<&str> = input.lines().collect();
+    let split = lines.iter().position(|line| line.trim().is_empty()).unwrap_or(lines.len());
+    let (diagram, instructions) = lines.split_at(split);
 
-            let c = line.chars().nth(m.start()).unwrap();
+    let (label_row, crate_rows) = diagram.split_last().ok_or(ParseError::MissingLabels)?;
+    let labels = parse::token_offsets(label_row);
+    if labels.is_empty() {
+        return Err(ParseError::MissingLabels);
+    }
 
-            if !('A'..='Z').contains(&c) {
-                break;
-            }
+    let mut result = SupplyStacks {
+        labels: Vec::new(),
+        stacks: vec![Vec::new(); labels.len()],
+        insn: Vec::new()
+    };
 
-            stack.push(c);
+    for &(_, label) in labels.iter() {
+        if result.labels.iter().any(|l| l == label) {
+            return Err(ParseError::DuplicateLabel { label: label.to_string() });
         }
 
-        result.stacks.insert(m.as_str().parse().unwrap(), Box::new(stack));
-        idx = m.end();
+        result.labels.push(label.to_string());
+    }
+
+    // crates are lined up with labels by character column, so non-ASCII labels still line up
+    let label_columns: Vec<(usize, usize)> = labels.iter()
+        .map(|&(offset, label)| (label_row[..offset].chars().count(), label.chars().count()))
+        .collect();
+
+    // read the diagram bottom up so each stack is built in order
+    for (i, line) in crate_rows.iter().enumerate().rev() {
+        for (start, end, c) in parse_crate_row(line.trim_end(), i + 1)? {
+            let stack = label_columns.iter()
+                .position(|&(column, width)| column <= end && start < column + width)
+                .ok_or(ParseError::UnalignedCrate { line: i + 1, column: start + 1 })?;
+
+            result.stacks[stack].push(c);
+        }
     }
 
-    println!();
+    for (i, line) in instructions.iter().enumerate().skip(1) {
+        let line_number = split + i + 1;
+        if line.trim().is_empty() {
+            continue;
+        }
 
-    for line in input.lines().skip(crates.len() + 2) {
-        let cap = INSN_LIST_REGEX.captures(line).unwrap();
-        result.insn.push((cap[1].parse().unwrap(), cap[2].parse().unwrap(), cap[3].parse().unwrap()));
+        let (count, from, to) = scan!(line.trim_end(), "move {} from {} to {}")
+            .map_err(|error| ParseError::InvalidInstruction { line: line_number, error })?;
+        result.insn.push(Instruction { line: line_number, count, from, to });
     }
 
-    result
+    Ok(result)
 }
 
-#[aoc(day = 5, part = 1)]
-fn part1(input: String) -> String {
-    let mut stacks = parse_input(&input);
+fn rearrange(input: &str, crane: &dyn Crane, snapshots: bool) -> String {
+    let mut stacks = parse_input(input).unwrap_or_else(|e| panic!("couldn't parse the input: {e}"));
 
-    stacks.process();
+    stacks.run(crane, snapshots).unwrap_or_else(|e| panic!("couldn't rearrange the crates: {e}"));
     stacks.pretty_print();
 
     stacks.peek_string()
 }
 
+#[aoc(day = 5, part = 1, title = "Supply Stacks")]
+fn part1(input: String) -> String {
+    rearrange(&input, &CrateMover9000, false)
+}
+
 #[aoc(day = 5, part = 2)]
 fn part2(input: String) -> String {
-    let mut stacks = parse_input(&input);
+    rearrange(&input, &CrateMover9001, false)
+}
 
-    stacks.process_9001();
-    stacks.pretty_print();
+#[aoc(day = 5, part = 1, variant = "steps")]
+fn part1_steps(input: String) -> String {
+    rearrange(&input, &CrateMover9000, true)
+}
 
-    stacks.peek_string()
+#[aoc(day = 5, part = 2, variant = "steps")]
+fn part2_steps(input: String) -> String {
+    rearrange(&input, &CrateMover9001, true)
 }
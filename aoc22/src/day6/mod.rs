@@ -1,31 +1,26 @@
-use std::collections::HashSet;
+use aoclib::window;
 
 struct CommSignal {
-    message: Vec<char>,
+    message: Vec<u8>,
 }
 
 impl CommSignal {
+    /// The number of characters processed once the last `block_size` were all different.
     fn find_start(&self, block_size: usize) -> Option<usize> {
-        for i in block_size..self.message.len() {
-            if self.message[i-block_size..i].iter().collect::<HashSet<_>>().len() >= block_size {
-                return Some(i);
-            }
-        }
-
-        return None;
+        window::first_distinct(&self.message, block_size).map(|window| window.end)
     }
 }
 
-#[aoc(day=6, part=1)]
+#[aoc(day=6, part=1, title = "Tuning Trouble")]
 fn part1(input: String) -> String {
     CommSignal {
-        message: input.chars().collect()
+        message: input.into_bytes()
     }.find_start(4).unwrap().to_string()
 }
 
 #[aoc(day=6, part=2)]
 fn part2(input: String) -> String {
     CommSignal {
-        message: input.chars().collect()
+        message: input.into_bytes()
     }.find_start(14).unwrap().to_string()
 }
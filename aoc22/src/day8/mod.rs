@@ -1,4 +1,5 @@
 use std::collections::HashSet;
+use aoclib::grid::Grid;
 
 enum ViewingDistance {
     Blocked(usize),
@@ -29,27 +30,13 @@ impl ViewingDistance {
     }
 }
 
-fn map_to_matrix(input: &String) -> Vec<Vec<i8>> {
-    let width = input.lines().map(|line| line.len()).max().unwrap();
-    let height = input.lines().count();
-
-    // trees[y][x] == height
-    let mut trees = vec![vec![0i8; width]; height];
-
-    for (j, line) in input.lines().enumerate() {
-        for (i, c) in line.chars().enumerate() {
-            trees[j][i] = match c {
-                '0'..='9' => (c as i8) - ('0' as i8),
-                _ => panic!("unsupported character found: {c}")
-            };
-        }
-    }
-
-    trees
+fn map_to_matrix(input: &str) -> Grid<i8> {
+    // trees[(x, y)] == height
+    Grid::parse(input, |c| c.to_digit(10).map(|d| d as i8)).unwrap()
 }
 
-fn calc_visibility(trees: &Vec<Vec<i8>>, x: usize, y: usize, width: usize, height: usize) -> [ViewingDistance; 4] {
-    let tree: i8 = trees[y][x];
+fn calc_visibility(trees: &Grid<i8>, x: usize, y: usize, width: usize, height: usize) -> [ViewingDistance; 4] {
+    let tree: i8 = trees[(x, y)];
     let mut up_vd;
     let mut down_vd;
     let mut left_vd;
@@ -62,8 +49,8 @@ fn calc_visibility(trees: &Vec<Vec<i8>>, x: usize, y: usize, width: usize, heigh
         up_vd = ViewingDistance::Open(up.len());
 
         for j in up {
-            if trees[j][x] >= tree {
-                // println!("tree of height {} at ({x}, {j}) is in the way of tree ({x}, {y}) of height {tree}", trees[j][x]);
+            if trees[(x, j)] >= tree {
+                // println!("tree of height {} at ({x}, {j}) is in the way of tree ({x}, {y}) of height {tree}", trees[(x, j)]);
                 up_vd = ViewingDistance::Blocked(y - j);
                 break;
             }
@@ -75,8 +62,8 @@ fn calc_visibility(trees: &Vec<Vec<i8>>, x: usize, y: usize, width: usize, heigh
         down_vd = ViewingDistance::Open(down.len());
 
         for j in down {
-            if trees[j][x] >= tree {
-                // println!("tree of height {} at ({x}, {j}) is in the way of tree ({x}, {y}) of height {tree}", trees[j][x]);
+            if trees[(x, j)] >= tree {
+                // println!("tree of height {} at ({x}, {j}) is in the way of tree ({x}, {y}) of height {tree}", trees[(x, j)]);
                 down_vd = ViewingDistance::Blocked(j - y);
                 break;
             }
@@ -90,8 +77,8 @@ fn calc_visibility(trees: &Vec<Vec<i8>>, x: usize, y: usize, width: usize, heigh
         left_vd = ViewingDistance::Open(left.len());
 
         for i in left {
-            if trees[y][i] >= tree {
-                // println!("tree of height {} at ({i}, {y}) is in the way of tree ({x}, {y}) of height {tree}", trees[y][i]);
+            if trees[(i, y)] >= tree {
+                // println!("tree of height {} at ({i}, {y}) is in the way of tree ({x}, {y}) of height {tree}", trees[(i, y)]);
                 left_vd = ViewingDistance::Blocked(x - i);
                 break;
             }
@@ -103,8 +90,8 @@ fn calc_visibility(trees: &Vec<Vec<i8>>, x: usize, y: usize, width: usize, heigh
         right_vd = ViewingDistance::Open(right.len());
 
         for i in right {
-            if trees[y][i] >= tree {
-                // println!("tree of height {} at ({i}, {y}) is in the way of tree ({x}, {y}) of height {tree}", trees[y][i]);
+            if trees[(i, y)] >= tree {
+                // println!("tree of height {} at ({i}, {y}) is in the way of tree ({x}, {y}) of height {tree}", trees[(i, y)]);
                 right_vd = ViewingDistance::Blocked(i - x);
                 break;
             }
@@ -115,14 +102,12 @@ fn calc_visibility(trees: &Vec<Vec<i8>>, x: usize, y: usize, width: usize, heigh
     [up_vd, down_vd, left_vd, right_vd]
 }
 
-fn count_visible(trees: &Vec<Vec<i8>>) -> u32 {
+fn count_visible(trees: &Grid<i8>) -> u32 {
     let mut visible = 0u32;
-    let height = trees.len();
-
-    for (j, row) in trees.iter().enumerate() {
-        let width = row.len();
+    let (width, height) = (trees.width(), trees.height());
 
-        for (i, _) in row.iter().enumerate() {
+    for j in 0..height {
+        for i in 0..width {
             if !(1..height-1).contains(&j) || !(1..width-1).contains(&i) {
                 // edges are always visible, and therefore a trivial case
                 println!("({i}, {j}) is visible as it's on the edge");
@@ -140,14 +125,12 @@ fn count_visible(trees: &Vec<Vec<i8>>) -> u32 {
     visible
 }
 
-fn max_scenic(trees: &Vec<Vec<i8>>) -> usize {
+fn max_scenic(trees: &Grid<i8>) -> usize {
     let mut scenic_scores: HashSet<usize> = HashSet::new();
-    let height = trees.len();
-
-    for (j, row) in trees.iter().enumerate() {
-        let width = row.len();
+    let (width, height) = (trees.width(), trees.height());
 
-        for (i, _) in row.iter().enumerate() {
+    for j in 0..height {
+        for i in 0..width {
             if !(1..height-1).contains(&j) || !(1..width-1).contains(&i) {
                 // edges always have a scenic score of 0, so we will skip them
                 continue;
@@ -160,7 +143,7 @@ fn max_scenic(trees: &Vec<Vec<i8>>) -> usize {
     *scenic_scores.iter().max().unwrap()
 }
 
-#[aoc(day=8, part=1)]
+#[aoc(day=8, part=1, title = "Treetop Tree House")]
 fn part1(input: String) -> String {
     let trees = map_to_matrix(&input);
 
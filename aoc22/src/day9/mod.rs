@@ -1,56 +1,10 @@
 use std::borrow::Borrow;
 use std::cell::Cell;
 use std::collections::HashSet;
-use std::ops::{Add, AddAssign, MulAssign, Sub};
 use std::rc::Rc;
+use aoclib::geom::Point2;
 
-#[derive(Eq, PartialEq, Hash, Copy, Clone, Debug)]
-struct Coord {
-    x: i32,
-    y: i32
-}
-
-impl Add for Coord {
-    type Output = Self;
-
-    fn add(self, rhs: Self) -> Self::Output {
-        return Self {
-            x: self.x + rhs.x,
-            y: self.y + rhs.y
-        }
-    }
-}
-
-impl Sub for Coord {
-    type Output = Self;
-
-    fn sub(self, rhs: Self) -> Self::Output {
-        return Self {
-            x: self.x - rhs.x,
-            y: self.y - rhs.y
-        }
-    }
-}
-
-impl AddAssign for Coord {
-    fn add_assign(&mut self, rhs: Self) {
-        self.x += rhs.x;
-        self.y += rhs.y;
-    }
-}
-
-impl MulAssign for Coord {
-    fn mul_assign(&mut self, rhs: Self) {
-        self.x *= rhs.x;
-        self.y *= rhs.y;
-    }
-}
-
-impl Default for Coord {
-    fn default() -> Self {
-        Coord { x: 0, y: 0 }
-    }
-}
+type Coord = Point2<i32>;
 
 trait RopeSegment {
     fn get_coord(&self) -> &Cell<Coord>;
@@ -231,7 +185,7 @@ fn simulate_n_tails(moves: &Vec<Movement>, n: u32) -> HashSet<Coord> {
     tracks
 }
 
-#[aoc(day=9, part=1)]
+#[aoc(day=9, part=1, title = "Rope Bridge")]
 fn part1(input: String) -> String {
     let moves = parse_moves(&input);
 
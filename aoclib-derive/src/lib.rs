@@ -1,23 +1,50 @@
 use once_cell::sync::Lazy;
 use proc_macro2::TokenStream;
-use quote::{format_ident, quote};
+use quote::{format_ident, quote, quote_spanned};
 use regex::Regex;
 use syn::*;
 
-fn get_day(input: &str) -> Option<u32> {
-    static RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^day\s*(?:=|=>)?\s*(\d{1,2})").unwrap());
-    RE.captures(input).and_then(|c| {
-        Some(c.get(1)?.as_str().parse().ok()?)
-    })
+struct AocArgs {
+    year: Option<u32>,
+    day: u32,
+    part: Option<u32>,
+    variant: Option<String>,
+    title: Option<String>,
 }
 
-fn get_day_and_part(input: &str) -> Option<(u32, u32)> {
-    static RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^day\s*(?:=|=>)?\s*(\d{1,2}),\s*part\s*(?:=|=>)?\s*([12])$").unwrap());
-    RE.captures(input).and_then(|c| {
-        let day = c.get(1)?.as_str().parse().ok()?;
-        let part = c.get(2)?.as_str().parse().ok()?;
-        Some((day, part))
-    })
+fn parse_args(input: &str) -> Option<AocArgs> {
+    static RE: Lazy<Regex> = Lazy::new(|| Regex::new(r#"^\s*([a-z_]+)\s*(?:=>|=)?\s*(\d+|"(?:[^"\\]|\\.)*")\s*(?:,|$)"#).unwrap());
+
+    let mut args = AocArgs { year: None, day: 0, part: None, variant: None, title: None };
+    let mut rest = input.trim();
+    while !rest.is_empty() {
+        let c = RE.captures(rest)?;
+        let value = c.get(2)?.as_str();
+        match c.get(1)?.as_str() {
+            "year" => args.year = Some(value.parse().ok()?),
+            "day" => args.day = value.parse().ok()?,
+            "part" => args.part = Some(value.parse().ok()?),
+            "variant" => args.variant = Some(parse_str::<LitStr>(value).ok()?.value()),
+            "title" => args.title = Some(parse_str::<LitStr>(value).ok()?.value()),
+            _ => return None,
+        }
+        rest = &rest[c.get(0)?.end()..];
+    }
+
+    if !(1..=25).contains(&args.day) {
+        return None;
+    }
+
+    Some(args)
+}
+
+fn variant_ident_suffix(variant: &Option<String>) -> Option<String> {
+    static RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^[A-Za-z0-9_-]+$").unwrap());
+    match variant {
+        Some(v) if RE.is_match(v) => Some(format!("_{}", v.replace('-', "_"))),
+        Some(_) => None,
+        None => Some(String::new()),
+    }
 }
 
 #[proc_macro_attribute]
@@ -25,24 +52,55 @@ pub fn aoc(attr: proc_macro::TokenStream, item: proc_macro::TokenStream) -> proc
     let attr = TokenStream::from(attr);
     let item = parse_macro_input!(item as ItemFn);
 
-    let (day, part) = get_day_and_part(&attr.to_string()).or_else(|| {
-        panic!("Invalid #[aoc] attribute: {}", attr.to_string());
-    }).unwrap();
+    let args = parse_args(&attr.to_string())
+        .filter(|args| matches!(args.part, Some(1 | 2)))
+        .unwrap_or_else(|| panic!("Invalid #[aoc] attribute: {}", attr));
+    let day = args.day;
+    let part = args.part.unwrap();
 
     let ident = item.sig.ident.to_owned();
 
-    let wrapper_ident = format_ident!("__aoclib_wrapper_day{}_part{}", day, part);
+    let suffix = variant_ident_suffix(&args.variant)
+        .unwrap_or_else(|| panic!("Invalid #[aoc] variant, only letters, digits, '_' and '-' are allowed: {}", attr));
+    let wrapper_ident = format_ident!("__aoclib_wrapper_day{}_part{}{}", day, part, suffix);
 
     assert_valid_input("aoc", &item);
 
     let call_token = quote! { #ident(input) };
 
+    let year_token = match args.year {
+        Some(year) => quote! { #year },
+        None => quote! { ::aoclib::__infer_year(env!("CARGO_PKG_NAME")) },
+    };
+    let variant_token = match args.variant {
+        Some(variant) => quote! { Some(#variant) },
+        None => quote! { None },
+    };
+    let name = ident.to_string();
+
+    let mut meta_token = quote_spanned! {ident.span()=>
+        ::aoclib::EntryMeta::new(#name, module_path!()).with_source(file!(), line!())
+    };
+    if let Some(doc) = get_doc(&item) {
+        meta_token = quote! { #meta_token.with_doc(#doc) };
+    }
+    if let Some(title) = args.title {
+        meta_token = quote! { #meta_token.with_title(#title) };
+    }
+
     let output: TokenStream = quote! {
         fn #wrapper_ident(input: ::std::string::String) -> ::std::string::String {
             #call_token
         }
         #item
-        ::aoclib::add_entry!(#day, #part, #wrapper_ident);
+        ::aoclib::add_entry!(
+            year = #year_token,
+            day = #day,
+            part = #part,
+            variant = #variant_token,
+            meta = #meta_token,
+            executor = #wrapper_ident
+        );
     };
 
     proc_macro::TokenStream::from(output)
@@ -53,9 +111,10 @@ pub fn aoc_test(attr: proc_macro::TokenStream, item: proc_macro::TokenStream) ->
     let attr = TokenStream::from(attr);
     let item = parse_macro_input!(item as ItemFn);
 
-    let day = get_day(&attr.to_string()).or_else(|| {
-        panic!("Invalid #[aoc_test] attribute: {}", attr.to_string());
-    }).unwrap();
+    let day = parse_args(&attr.to_string())
+        .filter(|args| args.part.is_none() && args.variant.is_none())
+        .unwrap_or_else(|| panic!("Invalid #[aoc_test] attribute: {}", attr))
+        .day;
 
     let ident = item.sig.ident.to_owned();
 
@@ -74,6 +133,23 @@ pub fn aoc_test(attr: proc_macro::TokenStream, item: proc_macro::TokenStream) ->
     proc_macro::TokenStream::from(output)
 }
 
+fn get_doc(item: &ItemFn) -> Option<String> {
+    let lines: Vec<String> = item.attrs.iter()
+        .filter(|attr| attr.path.is_ident("doc"))
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(Meta::NameValue(MetaNameValue { lit: Lit::Str(s), .. })) => Some(s.value()),
+            _ => None,
+        })
+        .map(|line| line.strip_prefix(' ').unwrap_or(&line).to_string())
+        .collect();
+
+    if lines.is_empty() {
+        None
+    } else {
+        Some(lines.join("\n"))
+    }
+}
+
 fn assert_valid_input(attr_name: &str, item: &ItemFn) {
     if item.sig.inputs.is_empty() {
         panic!("Invalid #[{}] function: no input arg: {}", attr_name, item.sig.ident);
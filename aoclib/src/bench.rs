@@ -0,0 +1,287 @@
+use std::fs::{self, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::memory::{self, AllocStats};
+use crate::AocEntry;
+
+pub const HISTORY_FILE_NAME: &str = "aoc-bench.jsonl";
+pub const DEFAULT_THRESHOLD_PERCENT: f64 = 10.0;
+
+/// One line of the history file: every entry timed by a single `bench` invocation.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BenchRecord {
+    pub timestamp: u64,
+    pub commit: Option<String>,
+    pub baseline: Option<String>,
+    pub entries: Vec<EntryTiming>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EntryTiming {
+    pub year: u32,
+    pub day: u32,
+    pub part: u32,
+    pub variant: Option<String>,
+    pub runs: u32,
+    pub min_ns: u64,
+    pub mean_ns: u64,
+    pub median_ns: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory: Option<AllocStats>,
+}
+
+impl EntryTiming {
+    pub fn label(&self) -> String {
+        let variant = self.variant.as_ref().map_or(String::new(), |v| format!(" [{v}]"));
+        format!("{} day {:>2} part {}{}", self.year, self.day, self.part, variant)
+    }
+
+    fn same_entry(&self, other: &EntryTiming) -> bool {
+        (self.year, self.day, self.part, &self.variant) == (other.year, other.day, other.part, &other.variant)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Regression {
+    pub label: String,
+    pub previous_ns: u64,
+    pub current_ns: u64,
+}
+
+impl Regression {
+    pub fn percent_slower(&self) -> f64 {
+        (self.current_ns as f64 / self.previous_ns as f64 - 1.0) * 100.0
+    }
+}
+
+/// Times `runs` executions of an entry, excluding input loading.
+/// Allocation stats, when enabled, come from the first run.
+pub fn time_entry(entry: &AocEntry, input: &str, runs: u32) -> EntryTiming {
+    let runs = runs.max(1);
+    let mut alloc_stats = None;
+    let mut samples: Vec<Duration> = (0..runs).map(|_| {
+        let input = input.to_string();
+        let (elapsed, stats) = memory::measure(|| {
+            let start = Instant::now();
+            entry.execute(input);
+            start.elapsed()
+        });
+        alloc_stats = alloc_stats.or(stats);
+        elapsed
+    }).collect();
+    samples.sort();
+
+    let total: Duration = samples.iter().sum();
+    EntryTiming {
+        year: entry.year(),
+        day: entry.day(),
+        part: entry.part(),
+        variant: entry.variant().map(str::to_string),
+        runs,
+        min_ns: samples[0].as_nanos() as u64,
+        mean_ns: (total / runs).as_nanos() as u64,
+        median_ns: samples[samples.len() / 2].as_nanos() as u64,
+        memory: alloc_stats,
+    }
+}
+
+/// The default history location, `target/aoc-bench.jsonl` next to the running binary's profile directory.
+pub fn default_history_path() -> PathBuf {
+    if let Some(dir) = std::env::var_os("CARGO_TARGET_DIR") {
+        return PathBuf::from(dir).join(HISTORY_FILE_NAME);
+    }
+
+    std::env::current_exe().ok()
+        .and_then(|exe| Some(exe.parent()?.parent()?.to_path_buf()))
+        .unwrap_or_else(|| PathBuf::from("target"))
+        .join(HISTORY_FILE_NAME)
+}
+
+/// The short hash of `HEAD` in `dir`, suffixed with `-dirty` if there are uncommitted changes.
+pub fn git_commit(dir: &str) -> Option<String> {
+    let git = |args: &[&str]| {
+        let output = Command::new("git").args(args).current_dir(dir).output().ok()?;
+        output.status.success().then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    };
+
+    let hash = git(&["rev-parse", "--short", "HEAD"])?;
+    let dirty = git(&["status", "--porcelain"]).is_some_and(|status| !status.is_empty());
+    Some(if dirty { format!("{hash}-dirty") } else { hash })
+}
+
+pub fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs())
+}
+
+pub fn append_history(path: &Path, record: &BenchRecord) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", serde_json::to_string(record)?)
+}
+
+pub fn read_history(path: &Path) -> io::Result<Vec<BenchRecord>> {
+    let file = match fs::File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => return Err(e),
+    };
+
+    let mut records = vec![];
+    for (i, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let record = serde_json::from_str(&line).map_err(|e| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("{}:{}: {}", path.display(), i + 1, e))
+        })?;
+        records.push(record);
+    }
+
+    Ok(records)
+}
+
+/// Picks the records to compare: the latest run against either the one before it
+/// or the most recent run saved under `baseline`.
+pub fn select_comparison<'a>(history: &'a [BenchRecord], baseline: Option<&str>) -> Result<(&'a BenchRecord, &'a BenchRecord), String> {
+    let (current, earlier) = history.split_last().ok_or("no benchmark runs recorded yet")?;
+
+    let previous = match baseline {
+        Some(name) => earlier.iter().rev().find(|record| record.baseline.as_deref() == Some(name))
+            .ok_or_else(|| format!("no benchmark run saved as baseline '{name}'"))?,
+        None => earlier.last().ok_or("only one benchmark run recorded, nothing to compare against")?,
+    };
+
+    Ok((previous, current))
+}
+
+/// Entries whose median time grew by more than `threshold_percent`.
+pub fn find_regressions(previous: &BenchRecord, current: &BenchRecord, threshold_percent: f64) -> Vec<Regression> {
+    current.entries.iter().filter_map(|cur| {
+        let prev = previous.entries.iter().find(|prev| prev.same_entry(cur))?;
+        let regression = Regression { label: cur.label(), previous_ns: prev.median_ns.max(1), current_ns: cur.median_ns };
+        // scaled rather than divided, so a change of exactly the threshold isn't pushed over it by rounding
+        let slower = regression.current_ns as f64 * 100.0 > regression.previous_ns as f64 * (100.0 + threshold_percent);
+        slower.then_some(regression)
+    }).collect()
+}
+
+pub fn print_comparison(previous: &BenchRecord, current: &BenchRecord) {
+    let describe = |record: &BenchRecord| {
+        let commit = record.commit.as_deref().unwrap_or("unknown commit");
+        match &record.baseline {
+            Some(name) => format!("{commit} ({name})"),
+            None => commit.to_string(),
+        }
+    };
+    println!("Comparing {} -> {}", describe(previous), describe(current));
+
+    for cur in &current.entries {
+        match previous.entries.iter().find(|prev| prev.same_entry(cur)) {
+            Some(prev) => {
+                let change = (cur.median_ns as f64 / prev.median_ns.max(1) as f64 - 1.0) * 100.0;
+                println!("  {}  {:>12} -> {:>12}  {:+.1}%", cur.label(), format_ns(prev.median_ns), format_ns(cur.median_ns), change);
+            }
+            None => println!("  {}  {:>12} -> {:>12}  (new)", cur.label(), "-", format_ns(cur.median_ns)),
+        }
+    }
+}
+
+pub fn format_ns(ns: u64) -> String {
+    match ns {
+        0..=999 => format!("{ns}ns"),
+        1_000..=999_999 => format!("{:.2}µs", ns as f64 / 1e3),
+        1_000_000..=999_999_999 => format!("{:.2}ms", ns as f64 / 1e6),
+        _ => format!("{:.2}s", ns as f64 / 1e9),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timing(day: u32, median_ns: u64) -> EntryTiming {
+        EntryTiming { year: 2022, day, part: 1, variant: None, runs: 1, min_ns: median_ns, mean_ns: median_ns, median_ns, memory: None }
+    }
+
+    fn record(baseline: Option<&str>, entries: Vec<EntryTiming>) -> BenchRecord {
+        BenchRecord { timestamp: 0, commit: None, baseline: baseline.map(str::to_string), entries }
+    }
+
+    #[test]
+    fn select_comparison_needs_two_runs() {
+        assert!(select_comparison(&[], None).is_err());
+        assert!(select_comparison(&[record(None, vec![])], None).is_err());
+
+        let history = [record(Some("a"), vec![timing(1, 1)]), record(None, vec![timing(1, 2)])];
+        let (previous, current) = select_comparison(&history, None).unwrap();
+        assert_eq!((previous.entries[0].median_ns, current.entries[0].median_ns), (1, 2));
+    }
+
+    #[test]
+    fn select_comparison_finds_latest_baseline() {
+        let history = [
+            record(Some("a"), vec![timing(1, 1)]),
+            record(Some("a"), vec![timing(1, 2)]),
+            record(None, vec![timing(1, 3)]),
+            record(None, vec![timing(1, 4)]),
+        ];
+        let (previous, current) = select_comparison(&history, Some("a")).unwrap();
+        assert_eq!((previous.entries[0].median_ns, current.entries[0].median_ns), (2, 4));
+        assert_eq!(select_comparison(&history, Some("b")).unwrap_err(), "no benchmark run saved as baseline 'b'");
+
+        // the latest run is never its own baseline
+        assert!(select_comparison(&history[..2], Some("a")).is_ok());
+        assert!(select_comparison(&history[..1], Some("a")).is_err());
+    }
+
+    #[test]
+    fn find_regressions_skips_unmatched_entries() {
+        let previous = record(None, vec![timing(1, 100), timing(2, 100)]);
+        let current = record(None, vec![timing(2, 500), timing(3, 500)]);
+        let regressions = find_regressions(&previous, &current, 10.0);
+        assert_eq!(regressions.len(), 1);
+        assert_eq!((regressions[0].label.as_str(), regressions[0].previous_ns, regressions[0].current_ns), ("2022 day  2 part 1", 100, 500));
+    }
+
+    #[test]
+    fn find_regressions_threshold_is_exclusive() {
+        let previous = record(None, vec![timing(1, 100), timing(2, 1000), timing(3, 0)]);
+        let current = record(None, vec![timing(1, 110), timing(2, 1101), timing(3, 2)]);
+        let days: Vec<_> = find_regressions(&previous, &current, 10.0).iter().map(|r| r.label.clone()).collect();
+        assert_eq!(days, ["2022 day  2 part 1", "2022 day  3 part 1"]);
+
+        assert!(find_regressions(&previous, &previous, 0.0).is_empty());
+    }
+
+    #[test]
+    fn history_round_trip() {
+        let path = std::env::temp_dir().join(format!("aoclib-bench-test-{}", std::process::id())).join(HISTORY_FILE_NAME);
+        let _ = fs::remove_file(&path);
+        assert!(read_history(&path).unwrap().is_empty());
+
+        let mut entry = timing(5, 42);
+        entry.variant = Some("fast".to_string());
+        append_history(&path, &record(Some("main"), vec![entry])).unwrap();
+        append_history(&path, &record(None, vec![timing(6, 7)])).unwrap();
+
+        let history = read_history(&path).unwrap();
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].baseline.as_deref(), Some("main"));
+        assert_eq!(history[0].entries[0].label(), "2022 day  5 part 1 [fast]");
+        assert_eq!(history[0].entries[0].median_ns, 42);
+        assert_eq!(history[1].baseline, None);
+        assert_eq!(history[1].entries[0].label(), "2022 day  6 part 1");
+    }
+}
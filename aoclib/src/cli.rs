@@ -0,0 +1,585 @@
+use std::fmt;
+use std::path::PathBuf;
+
+use crate::bench::{self, BenchRecord};
+use crate::{memory, registry, AocEntry};
+
+pub const EXIT_SUCCESS: i32 = 0;
+pub const EXIT_NO_ENTRY: i32 = 1;
+pub const EXIT_USAGE: i32 = 2;
+pub const EXIT_FAILED: i32 = 3;
+pub const EXIT_REGRESSION: i32 = 4;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Command {
+    Run(RunArgs),
+    Bench(RunArgs, BenchArgs),
+    Compare(CompareArgs),
+    List,
+    Help,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RunArgs {
+    pub days: DaySelection,
+    pub part: Option<u32>,
+    pub year: Option<u32>,
+    pub variant: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BenchArgs {
+    pub runs: u32,
+    pub baseline: Option<String>,
+    pub history: Option<PathBuf>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CompareArgs {
+    pub baseline: Option<String>,
+    pub threshold: f64,
+    pub history: Option<PathBuf>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DaySelection {
+    All,
+    Latest,
+    Range(u32, u32),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CliError {
+    MissingDays,
+    InvalidDay(String),
+    InvalidRange(String),
+    InvalidPart(String),
+    InvalidYear(String),
+    InvalidNumber(String, String),
+    MissingValue(String),
+    UnknownOption(String),
+    OptionNotAllowed(String, &'static str),
+    UnexpectedArgument(String),
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingDays => write!(f, "no day given"),
+            Self::InvalidDay(s) => write!(f, "invalid day '{s}': expected a number from 1 to 25, 'all' or 'latest'"),
+            Self::InvalidRange(s) => write!(f, "invalid day range '{s}': the start must not be after the end"),
+            Self::InvalidPart(s) => write!(f, "invalid part '{s}': expected 1 or 2"),
+            Self::InvalidYear(s) => write!(f, "invalid year '{s}'"),
+            Self::InvalidNumber(opt, s) => write!(f, "invalid value '{s}' for option '{opt}'"),
+            Self::MissingValue(opt) => write!(f, "option '{opt}' requires a value"),
+            Self::UnknownOption(opt) => write!(f, "unknown option '{opt}'"),
+            Self::OptionNotAllowed(opt, command) => write!(f, "option '{opt}' cannot be used with '{command}'"),
+            Self::UnexpectedArgument(arg) => write!(f, "unexpected argument '{arg}'"),
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
+
+impl CliError {
+    /// Every parse error is a usage error; the other exit codes come from running the command.
+    pub fn exit_code(&self) -> i32 {
+        EXIT_USAGE
+    }
+}
+
+impl DaySelection {
+    pub fn parse(s: &str) -> Result<Self, CliError> {
+        match s {
+            "all" => return Ok(Self::All),
+            "latest" => return Ok(Self::Latest),
+            _ => {}
+        }
+
+        if let Some((start, end)) = s.split_once('-') {
+            let start = parse_day(start).map_err(|_| CliError::InvalidDay(s.to_string()))?;
+            let end = parse_day(end).map_err(|_| CliError::InvalidDay(s.to_string()))?;
+            if start > end {
+                return Err(CliError::InvalidRange(s.to_string()));
+            }
+
+            return Ok(Self::Range(start, end));
+        }
+
+        let day = parse_day(s)?;
+        Ok(Self::Range(day, day))
+    }
+
+    /// Resolves the selection against the days available in a year.
+    pub fn resolve(&self, available: &[u32]) -> Vec<u32> {
+        match *self {
+            Self::All => available.to_vec(),
+            Self::Latest => available.last().copied().into_iter().collect(),
+            Self::Range(start, end) => available.iter().copied().filter(|day| (start..=end).contains(day)).collect(),
+        }
+    }
+}
+
+fn parse_day(s: &str) -> Result<u32, CliError> {
+    match s.parse::<u32>() {
+        Ok(day) if (1..=25).contains(&day) => Ok(day),
+        _ => Err(CliError::InvalidDay(s.to_string())),
+    }
+}
+
+fn parse_part(s: &str) -> Result<u32, CliError> {
+    match s.parse::<u32>() {
+        Ok(part) if (1..=2).contains(&part) => Ok(part),
+        _ => Err(CliError::InvalidPart(s.to_string())),
+    }
+}
+
+fn parse_year(s: &str) -> Result<u32, CliError> {
+    match s.parse::<u32>() {
+        Ok(year) if year >= 2015 => Ok(year),
+        _ => Err(CliError::InvalidYear(s.to_string())),
+    }
+}
+
+#[derive(Default)]
+struct Options<'a> {
+    given: Vec<&'a str>,
+    part: Option<u32>,
+    year: Option<u32>,
+    variant: Option<String>,
+    runs: Option<u32>,
+    baseline: Option<String>,
+    threshold: Option<f64>,
+    history: Option<PathBuf>,
+}
+
+impl Options<'_> {
+    fn only_allow(&self, command: &'static str, allowed: &[&str]) -> Result<(), CliError> {
+        match self.given.iter().find(|opt| !allowed.contains(opt)) {
+            Some(opt) => Err(CliError::OptionNotAllowed(opt.to_string(), command)),
+            None => Ok(()),
+        }
+    }
+}
+
+const RUN_OPTIONS: &[&str] = &["--part", "--year", "--variant"];
+const BENCH_OPTIONS: &[&str] = &["--part", "--year", "--variant", "--runs", "--baseline", "--history"];
+const COMPARE_OPTIONS: &[&str] = &["--baseline", "--threshold", "--history"];
+
+fn parse_number<T: std::str::FromStr>(opt: &str, s: &str) -> Result<T, CliError> {
+    s.parse().map_err(|_| CliError::InvalidNumber(opt.to_string(), s.to_string()))
+}
+
+/// A percentage, which must be a finite number no less than zero.
+fn parse_threshold(opt: &str, s: &str) -> Result<f64, CliError> {
+    match parse_number::<f64>(opt, s)? {
+        threshold if threshold.is_finite() && threshold >= 0.0 => Ok(threshold),
+        _ => Err(CliError::InvalidNumber(opt.to_string(), s.to_string())),
+    }
+}
+
+/// Parses the arguments after the program name.
+pub fn parse_args<S: AsRef<str>>(args: &[S]) -> Result<Command, CliError> {
+    let mut args = args.iter().map(|s| s.as_ref());
+
+    let mut positional: Vec<&str> = vec![];
+    let mut opts = Options::default();
+
+    while let Some(arg) = args.next() {
+        let (opt, inline_value) = match arg.split_once('=') {
+            Some((opt, value)) if opt.starts_with("--") => (opt, Some(value)),
+            _ => (arg, None),
+        };
+
+        let long = match opt {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-p" | "--part" => "--part",
+            "-y" | "--year" => "--year",
+            "-v" | "--variant" => "--variant",
+            "-n" | "--runs" => "--runs",
+            "--baseline" | "--threshold" | "--history" => opt,
+            _ if opt.starts_with('-') && opt.len() > 1 => return Err(CliError::UnknownOption(opt.to_string())),
+            _ => {
+                positional.push(arg);
+                continue;
+            }
+        };
+
+        let value = inline_value.or_else(|| args.next()).ok_or_else(|| CliError::MissingValue(opt.to_string()))?;
+        match long {
+            "--part" => opts.part = Some(parse_part(value)?),
+            "--year" => opts.year = Some(parse_year(value)?),
+            "--variant" => opts.variant = Some(value.to_string()),
+            "--runs" => opts.runs = Some(parse_number::<u32>(long, value)?.max(1)),
+            "--baseline" => opts.baseline = Some(value.to_string()),
+            "--threshold" => opts.threshold = Some(parse_threshold(long, value)?),
+            _ => opts.history = Some(PathBuf::from(value)),
+        }
+        opts.given.push(long);
+    }
+
+    let mut positional = positional.into_iter();
+    let command = match positional.next() {
+        Some(cmd @ ("help" | "list" | "run" | "bench" | "compare")) => cmd,
+        Some(days) => {
+            opts.only_allow("run", RUN_OPTIONS)?;
+            return parse_run_args(days, positional, opts).map(Command::Run);
+        }
+        None => return Err(CliError::MissingDays),
+    };
+
+    match command {
+        "help" => Ok(Command::Help),
+        "list" | "compare" => {
+            if let Some(arg) = positional.next() {
+                return Err(CliError::UnexpectedArgument(arg.to_string()));
+            }
+
+            if command == "list" {
+                opts.only_allow("list", &[])?;
+                return Ok(Command::List);
+            }
+
+            opts.only_allow("compare", COMPARE_OPTIONS)?;
+            Ok(Command::Compare(CompareArgs {
+                baseline: opts.baseline,
+                threshold: opts.threshold.unwrap_or(bench::DEFAULT_THRESHOLD_PERCENT),
+                history: opts.history,
+            }))
+        }
+        "bench" => {
+            opts.only_allow("bench", BENCH_OPTIONS)?;
+            let days = positional.next().ok_or(CliError::MissingDays)?;
+            let bench_args = BenchArgs {
+                runs: opts.runs.unwrap_or(1),
+                baseline: opts.baseline.take(),
+                history: opts.history.take(),
+            };
+            parse_run_args(days, positional, opts).map(|run_args| Command::Bench(run_args, bench_args))
+        }
+        _ => {
+            opts.only_allow("run", RUN_OPTIONS)?;
+            let days = positional.next().ok_or(CliError::MissingDays)?;
+            parse_run_args(days, positional, opts).map(Command::Run)
+        }
+    }
+}
+
+fn parse_run_args<'a>(days: &str, mut rest: impl Iterator<Item = &'a str>, opts: Options) -> Result<RunArgs, CliError> {
+    let days = DaySelection::parse(days)?;
+    let mut part = opts.part;
+    if let Some(p) = rest.next() {
+        part = Some(parse_part(p)?);
+    }
+
+    if let Some(arg) = rest.next() {
+        return Err(CliError::UnexpectedArgument(arg.to_string()));
+    }
+
+    Ok(RunArgs { days, part, year: opts.year, variant: opts.variant })
+}
+
+pub fn print_usage(program: &str) {
+    println!("Usage: {program} [run] <days> [part] [options]");
+    println!("       {program} bench <days> [part] [options]");
+    println!("       {program} compare [options]");
+    println!("       {program} list");
+    println!("       {program} help");
+    println!();
+    println!("Days:");
+    println!("  7            a single day");
+    println!("  1-5          an inclusive range of days");
+    println!("  all          every registered day");
+    println!("  latest       the most recent registered day");
+    println!();
+    println!("Options:");
+    println!("  -p, --part <1|2>       only run the given part");
+    println!("  -y, --year <year>      select a year (defaults to the latest registered)");
+    println!("  -v, --variant <name>   run a named variant instead of the default solution");
+    println!("  -n, --runs <count>     bench: time each entry this many times (default 1)");
+    println!("  --baseline <name>      bench: save the run as a named baseline");
+    println!("                         compare: compare against that baseline instead of the previous run");
+    println!("  --threshold <percent>  compare: flag entries slower by more than this (default {})", bench::DEFAULT_THRESHOLD_PERCENT);
+    println!("  --history <path>       bench/compare: history file (default target/{})", bench::HISTORY_FILE_NAME);
+    println!("  -h, --help             print this message");
+}
+
+fn select_entries(args: &RunArgs) -> Vec<&'static AocEntry> {
+    let registry = registry();
+    let Some(year) = args.year.or_else(|| registry.latest_year()) else {
+        return vec![];
+    };
+
+    let mut selected = vec![];
+    for day in args.days.resolve(&registry.days(year)) {
+        for part in registry.parts(year, day) {
+            if args.part.is_some_and(|p| p != part) {
+                continue;
+            }
+
+            let variants = registry.variants(year, day, part);
+            let entry = match &args.variant {
+                Some(name) => variants.iter().find(|entry| entry.variant() == Some(name.as_str())),
+                None => variants.first(),
+            };
+
+            selected.extend(entry);
+        }
+    }
+
+    selected
+}
+
+fn describe_selection(args: &RunArgs) -> String {
+    let mut desc = match args.days {
+        DaySelection::All => "any day".to_string(),
+        DaySelection::Latest => "the latest day".to_string(),
+        DaySelection::Range(start, end) if start == end => format!("day {start}"),
+        DaySelection::Range(start, end) => format!("days {start}-{end}"),
+    };
+
+    if let Some(part) = args.part {
+        desc += &format!(" part {part}");
+    }
+    if let Some(year) = args.year {
+        desc += &format!(" in {year}");
+    }
+    if let Some(variant) = &args.variant {
+        desc += &format!(" (variant {variant})");
+    }
+
+    desc
+}
+
+pub fn run(package_dir: &str, args: &RunArgs) -> i32 {
+    let entries = select_entries(args);
+    if entries.is_empty() {
+        eprintln!("No entry found for {}", describe_selection(args));
+        return EXIT_NO_ENTRY;
+    }
+
+    let mut code = EXIT_SUCCESS;
+    for entry in entries {
+        let input = crate::__load_test_data(package_dir, entry.day());
+        match registry().title(entry.year(), entry.day()) {
+            Some(title) => println!("============== Day {:>2} Part {}: {} ==============", entry.day(), entry.part(), title),
+            None => println!("============== Day {:>2} Part {} Output ==============", entry.day(), entry.part()),
+        }
+
+        let result = std::panic::catch_unwind(|| {
+            memory::measure(|| {
+                let start = std::time::Instant::now();
+                let output = entry.execute(input);
+                (output, start.elapsed())
+            })
+        });
+
+        match result {
+            Ok(((output, elapsed), alloc_stats)) => {
+                println!("{output}");
+                match alloc_stats {
+                    Some(stats) => println!("(took {}; {})", bench::format_ns(elapsed.as_nanos() as u64), stats),
+                    None => println!("(took {})", bench::format_ns(elapsed.as_nanos() as u64)),
+                }
+            }
+            Err(_) => {
+                eprintln!("{} failed (solution at {})", entry.meta.name, entry.meta.location());
+                code = EXIT_FAILED;
+            }
+        }
+    }
+
+    code
+}
+
+pub fn run_bench(package_dir: &str, args: &RunArgs, bench_args: &BenchArgs) -> i32 {
+    let entries = select_entries(args);
+    if entries.is_empty() {
+        eprintln!("No entry found for {}", describe_selection(args));
+        return EXIT_NO_ENTRY;
+    }
+
+    let mut timings = vec![];
+    for entry in entries {
+        let input = crate::__load_test_data(package_dir, entry.day());
+        match std::panic::catch_unwind(|| bench::time_entry(entry, &input, bench_args.runs)) {
+            Ok(timing) => timings.push(timing),
+            Err(_) => {
+                eprintln!("{} failed (solution at {})", entry.meta.name, entry.meta.location());
+                return EXIT_FAILED;
+            }
+        }
+    }
+
+    println!("==================== Benchmark ====================");
+    for timing in &timings {
+        println!("  {}  median {:>10}  min {:>10}  mean {:>10}  ({} runs)", timing.label(),
+            bench::format_ns(timing.median_ns), bench::format_ns(timing.min_ns), bench::format_ns(timing.mean_ns), timing.runs);
+        if let Some(stats) = &timing.memory {
+            println!("      {stats}");
+        }
+    }
+
+    let record = BenchRecord {
+        timestamp: bench::now(),
+        commit: bench::git_commit(package_dir),
+        baseline: bench_args.baseline.clone(),
+        entries: timings,
+    };
+
+    let path = bench_args.history.clone().unwrap_or_else(bench::default_history_path);
+    if let Err(e) = bench::append_history(&path, &record) {
+        eprintln!("error: could not write benchmark history to {}: {e}", path.display());
+        return EXIT_FAILED;
+    }
+
+    println!("Saved to {}", path.display());
+    EXIT_SUCCESS
+}
+
+pub fn run_compare(args: &CompareArgs) -> i32 {
+    let path = args.history.clone().unwrap_or_else(bench::default_history_path);
+    let history = match bench::read_history(&path) {
+        Ok(history) => history,
+        Err(e) => {
+            eprintln!("error: could not read benchmark history from {}: {e}", path.display());
+            return EXIT_FAILED;
+        }
+    };
+
+    let (previous, current) = match bench::select_comparison(&history, args.baseline.as_deref()) {
+        Ok(pair) => pair,
+        Err(e) => {
+            eprintln!("error: {e}");
+            return EXIT_NO_ENTRY;
+        }
+    };
+
+    bench::print_comparison(previous, current);
+
+    let regressions = bench::find_regressions(previous, current, args.threshold);
+    if regressions.is_empty() {
+        println!("No regressions above {}%", args.threshold);
+        return EXIT_SUCCESS;
+    }
+
+    println!("{} regression(s) above {}%:", regressions.len(), args.threshold);
+    for regression in &regressions {
+        println!("  {}  {:+.1}%", regression.label, regression.percent_slower());
+    }
+
+    EXIT_REGRESSION
+}
+
+/// Parses the process arguments, runs the requested command and returns the exit code.
+pub fn main(package_dir: &str) -> i32 {
+    let args: Vec<String> = std::env::args().collect();
+    let program = args.first().map_or("aoc", |s| s.as_str());
+
+    match parse_args(&args[1.min(args.len())..]) {
+        Ok(Command::Help) => {
+            print_usage(program);
+            EXIT_SUCCESS
+        }
+        Ok(Command::List) => {
+            registry().print_list();
+            EXIT_SUCCESS
+        }
+        Ok(Command::Run(run_args)) => run(package_dir, &run_args),
+        Ok(Command::Bench(run_args, bench_args)) => run_bench(package_dir, &run_args, &bench_args),
+        Ok(Command::Compare(compare_args)) => run_compare(&compare_args),
+        Err(e) => {
+            eprintln!("error: {e}");
+            eprintln!("Run '{program} --help' for usage.");
+            e.exit_code()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn threshold(value: &str) -> Result<f64, CliError> {
+        match parse_args(&["compare", "--threshold", value])? {
+            Command::Compare(args) => Ok(args.threshold),
+            other => panic!("parsed as {other:?}"),
+        }
+    }
+
+    fn run_args(days: DaySelection, part: Option<u32>) -> Command {
+        Command::Run(RunArgs { days, part, year: None, variant: None })
+    }
+
+    #[test]
+    fn day_selection_parse() {
+        let cases = [
+            ("7", Ok(DaySelection::Range(7, 7))),
+            ("1-5", Ok(DaySelection::Range(1, 5))),
+            ("all", Ok(DaySelection::All)),
+            ("latest", Ok(DaySelection::Latest)),
+            ("5-1", Err(CliError::InvalidRange("5-1".to_string()))),
+            ("20-30", Err(CliError::InvalidDay("20-30".to_string()))),
+            ("0", Err(CliError::InvalidDay("0".to_string()))),
+            ("26", Err(CliError::InvalidDay("26".to_string()))),
+            ("seven", Err(CliError::InvalidDay("seven".to_string()))),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(DaySelection::parse(input), expected, "{input}");
+        }
+    }
+
+    #[test]
+    fn parse_args_accepts_commands_and_options() {
+        let cases: [(&[&str], Command); 9] = [
+            (&["3"], run_args(DaySelection::Range(3, 3), None)),
+            (&["3", "2"], run_args(DaySelection::Range(3, 3), Some(2))),
+            (&["run", "1-5", "--part", "1"], run_args(DaySelection::Range(1, 5), Some(1))),
+            (&["run", "all", "-p", "2"], run_args(DaySelection::All, Some(2))),
+            (&["latest", "--part=2"], run_args(DaySelection::Latest, Some(2))),
+            (&["list"], Command::List),
+            (&["help"], Command::Help),
+            (&["--help"], Command::Help),
+            (&["run", "seven", "-h"], Command::Help),
+        ];
+        for (args, expected) in cases {
+            assert_eq!(parse_args(args), Ok(expected), "{args:?}");
+        }
+    }
+
+    #[test]
+    fn parse_args_errors_are_usage_errors() {
+        let cases: [(&[&str], CliError); 12] = [
+            (&[], CliError::MissingDays),
+            (&["run"], CliError::MissingDays),
+            (&["frobnicate"], CliError::InvalidDay("frobnicate".to_string())),
+            (&["run", "seven"], CliError::InvalidDay("seven".to_string())),
+            (&["5-1"], CliError::InvalidRange("5-1".to_string())),
+            (&["1", "--part", "3"], CliError::InvalidPart("3".to_string())),
+            (&["1", "--part=x"], CliError::InvalidPart("x".to_string())),
+            (&["1", "0"], CliError::InvalidPart("0".to_string())),
+            (&["1", "--part"], CliError::MissingValue("--part".to_string())),
+            (&["1", "--frobnicate"], CliError::UnknownOption("--frobnicate".to_string())),
+            (&["list", "--runs", "3"], CliError::OptionNotAllowed("--runs".to_string(), "list")),
+            (&["1", "2", "3"], CliError::UnexpectedArgument("3".to_string())),
+        ];
+        for (args, expected) in cases {
+            let err = parse_args(args).unwrap_err();
+            assert_eq!(err, expected, "{args:?}");
+            assert_eq!(err.exit_code(), EXIT_USAGE, "{args:?}");
+        }
+    }
+
+    #[test]
+    fn threshold_accepts_non_negative_percentages() {
+        assert_eq!(threshold("0"), Ok(0.0));
+        assert_eq!(threshold("12.5"), Ok(12.5));
+    }
+
+    #[test]
+    fn threshold_rejects_nan_infinite_and_negative_values() {
+        for value in ["NaN", "inf", "-inf", "-1", "x"] {
+            assert_eq!(threshold(value), Err(CliError::InvalidNumber("--threshold".to_string(), value.to_string())));
+        }
+    }
+}
@@ -0,0 +1,252 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// A repeating sequence: after `start` steps, every `length` steps return to the same state.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    /// The earliest step index whose state matches the state after `n` steps.
+    pub fn equivalent_step(&self, n: u64) -> usize {
+        if n < self.start as u64 {
+            return n as usize;
+        }
+
+        self.start + ((n - self.start as u64) % self.length as u64) as usize
+    }
+}
+
+/// Finds the cycle by remembering every state seen. Uses the most memory, but calls `step`
+/// the fewest times.
+pub fn find_hashed<S: Clone + Hash + Eq>(initial: S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    let mut seen = HashMap::new();
+    let mut state = initial;
+
+    for i in 0.. {
+        if let Some(start) = seen.insert(state.clone(), i) {
+            return Cycle { start, length: i - start };
+        }
+        state = step(&state);
+    }
+
+    unreachable!()
+}
+
+/// Floyd's tortoise and hare: constant memory, at the cost of roughly three `step`s per state.
+pub fn floyd<S: Clone + PartialEq>(initial: S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    let mut tortoise = step(&initial);
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        hare = step(&hare);
+    }
+
+    let mut start = 0;
+    tortoise = initial;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    let mut length = 1;
+    hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        length += 1;
+    }
+
+    Cycle { start, length }
+}
+
+/// Brent's algorithm: constant memory and usually fewer `step`s than [`floyd`].
+pub fn brent<S: Clone + PartialEq>(initial: S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial);
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+    }
+
+    tortoise = initial.clone();
+    hare = initial;
+    for _ in 0..length {
+        hare = step(&hare);
+    }
+
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    Cycle { start, length }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Extrapolated {
+    pub value: i64,
+    /// The cycle used to skip ahead, or `None` if `n` was reached by simulating every step.
+    pub cycle: Option<Cycle>,
+}
+
+/// Computes an accumulated metric after `n` steps of a simulation too large to store or clone
+/// whole, such as a growing tower.
+///
+/// Each call to `step` advances the simulation once and returns a key describing the step
+/// along with the metric afterwards. A key recurring at a steady distance, with the metric
+/// growing by the same amount each time, marks a cycle that is used to jump to step `n`.
+/// When the key captures the entire state, pass 0 `confirmations`; when it is only an
+/// approximation, require that many extra full periods to repeat before trusting it.
+pub fn extrapolate<K: Hash + Eq>(n: u64, initial_metric: i64, confirmations: usize, mut step: impl FnMut() -> (K, i64)) -> Extrapolated {
+    let mut metrics = vec![initial_metric];
+    let mut last_seen: HashMap<K, usize> = HashMap::new();
+    let mut period = 0;
+    let mut run = 0;
+
+    for t in 1..=n as usize {
+        let (key, metric) = step();
+        metrics.push(metric);
+
+        match last_seen.insert(key, t) {
+            Some(prev) if metrics[t] - metrics[t - 1] == metrics[prev] - metrics[prev - 1] => {
+                let length = t - prev;
+                if length == period {
+                    run += 1;
+                } else {
+                    period = length;
+                    run = 1;
+                }
+            }
+            _ => {
+                period = 0;
+                run = 0;
+            }
+        }
+
+        if period > 0 && run > confirmations * period {
+            let cycle = Cycle { start: t - period, length: period };
+            let remaining = n - t as u64;
+            let (full, rest) = (remaining / period as u64, (remaining % period as u64) as usize);
+            let per_cycle = metrics[t] - metrics[t - period];
+
+            return Extrapolated {
+                value: metrics[t - period + rest] + (full as i64 + 1) * per_cycle,
+                cycle: Some(cycle),
+            };
+        }
+    }
+
+    Extrapolated { value: metrics[n as usize], cycle: None }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 0, 1, 2, 3, 4, 5, then 3, 4, 5 forever.
+    fn step(&x: &u32) -> u32 {
+        if x == 5 { 3 } else { x + 1 }
+    }
+
+    #[test]
+    fn finders_agree() {
+        let expected = Cycle { start: 3, length: 3 };
+        assert_eq!(find_hashed(0, step), expected);
+        assert_eq!(floyd(0, step), expected);
+        assert_eq!(brent(0, step), expected);
+    }
+
+    #[test]
+    fn finders_on_pseudo_random_sequence() {
+        let f = |&x: &u64| (x * x + 1) % 1009;
+        let expected = find_hashed(7, f);
+        assert_eq!(floyd(7, f), expected);
+        assert_eq!(brent(7, f), expected);
+    }
+
+    #[test]
+    fn immediate_fixed_point() {
+        let cycle = brent(4, |&x: &u32| x);
+        assert_eq!(cycle, Cycle { start: 0, length: 1 });
+        assert_eq!(floyd(4, |&x: &u32| x), cycle);
+    }
+
+    #[test]
+    fn equivalent_step() {
+        let cycle = Cycle { start: 3, length: 3 };
+        assert_eq!(cycle.equivalent_step(2), 2);
+        assert_eq!(cycle.equivalent_step(3), 3);
+        assert_eq!(cycle.equivalent_step(7), 4);
+        assert_eq!(cycle.equivalent_step(1_000_000_000_000), 4);
+    }
+
+    /// A metric that grows by 1, 2, 3 and then repeatedly by 4, 5, 6, keyed by where it is in that.
+    fn growth() -> impl FnMut() -> (usize, i64) {
+        let increments = [1, 2, 3, 4, 5, 6];
+        let (mut t, mut total) = (0, 0);
+        move || {
+            let i = if t < 3 { t } else { 3 + (t - 3) % 3 };
+            t += 1;
+            total += increments[i];
+            (i, total)
+        }
+    }
+
+    fn brute_force(n: u64) -> i64 {
+        let mut step = growth();
+        (0..n).map(|_| step().1).last().unwrap_or(0)
+    }
+
+    #[test]
+    fn extrapolate_matches_brute_force() {
+        for n in [0, 1, 3, 5, 6, 7, 20, 101] {
+            assert_eq!(extrapolate(n, 0, 0, growth()).value, brute_force(n), "n = {n}");
+        }
+
+        let result = extrapolate(1_000_000_000, 0, 0, growth());
+        assert_eq!(result.cycle.map(|cycle| cycle.length), Some(3));
+        assert_eq!(result.value, 6 + (1_000_000_000 - 3) / 3 * 15 + 4);
+    }
+
+    #[test]
+    fn extrapolate_without_cycle_simulates() {
+        let mut t = 0;
+        let result = extrapolate(10, 5, 0, || {
+            t += 1;
+            (t, t as i64 * t as i64)
+        });
+        assert_eq!(result, Extrapolated { value: 100, cycle: None });
+    }
+
+    /// Keyed only by parity, so the key repeats before the metric settles into its real pattern
+    /// of growing by 3 each step.
+    fn approximate() -> impl FnMut() -> (usize, i64) {
+        let increments = [1, 2, 1, 2, 1];
+        let (mut t, mut total) = (0, 0);
+        move || {
+            total += increments.get(t).copied().unwrap_or(3);
+            t += 1;
+            (t % 2, total)
+        }
+    }
+
+    #[test]
+    fn confirmations_reject_a_premature_repeat() {
+        let expected = 7 + 995 * 3;
+        assert_ne!(extrapolate(1000, 0, 0, approximate()).value, expected);
+        assert_eq!(extrapolate(1000, 0, 2, approximate()).value, expected);
+    }
+}
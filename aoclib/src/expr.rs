@@ -0,0 +1,437 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// An integer arithmetic expression over named variables, e.g. `old * (old + 3) % 7`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Expr {
+    Number(i64),
+    Variable(String),
+    Neg(Box<Expr>),
+    Binary { op: BinOp, lhs: Box<Expr>, rhs: Box<Expr> },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    /// Integer division, rounding towards zero.
+    Div,
+    Rem,
+}
+
+impl BinOp {
+    fn precedence(self) -> u8 {
+        match self {
+            Self::Add | Self::Sub => 1,
+            Self::Mul | Self::Div | Self::Rem => 2,
+        }
+    }
+
+    fn symbol(self) -> char {
+        match self {
+            Self::Add => '+',
+            Self::Sub => '-',
+            Self::Mul => '*',
+            Self::Div => '/',
+            Self::Rem => '%',
+        }
+    }
+
+    fn from_symbol(c: char) -> Option<Self> {
+        [Self::Add, Self::Sub, Self::Mul, Self::Div, Self::Rem].into_iter().find(|op| op.symbol() == c)
+    }
+}
+
+/// Columns count characters from 0, so they point at the right place in non-ASCII input too.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ExprErrorKind {
+    /// A character that can't start any token.
+    UnexpectedChar { column: usize, found: char },
+    UnexpectedToken { column: usize, found: String, expected: &'static str },
+    UnexpectedEnd { expected: &'static str },
+    NumberTooLarge { column: usize, digits: String },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExprError {
+    pub input: String,
+    pub kind: ExprErrorKind,
+}
+
+impl fmt::Display for ExprError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            ExprErrorKind::UnexpectedChar { column, found } => {
+                write!(f, "unexpected {:?} at column {} of {:?}", found, column + 1, self.input)
+            }
+            ExprErrorKind::UnexpectedToken { column, found, expected } => {
+                write!(f, "expected {} at column {} of {:?}, found {:?}", expected, column + 1, self.input, found)
+            }
+            ExprErrorKind::UnexpectedEnd { expected } => {
+                write!(f, "expected {} at the end of {:?}", expected, self.input)
+            }
+            ExprErrorKind::NumberTooLarge { column, digits } => {
+                write!(f, "number {} at column {} of {:?} does not fit in an i64", digits, column + 1, self.input)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ExprError {}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EvalError {
+    UnknownVariable { name: String },
+    DivisionByZero,
+    Overflow,
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownVariable { name } => write!(f, "no value for variable {name}"),
+            Self::DivisionByZero => write!(f, "division by zero"),
+            Self::Overflow => write!(f, "arithmetic overflow"),
+        }
+    }
+}
+
+impl std::error::Error for EvalError {}
+
+impl Expr {
+    /// Parses the usual infix notation: `+ - * / %` with `*`, `/` and `%` binding tighter, all left
+    /// associative; unary minus; parentheses; non-negative integer literals; and variable names
+    /// made of letters, digits and underscores.
+    pub fn parse(input: &str) -> Result<Expr, ExprError> {
+        let mut parser = Parser { input, tokens: tokenize(input)?, next: 0 };
+        let expr = parser.sum()?;
+
+        match parser.peek() {
+            None => Ok(expr),
+            Some(_) => Err(parser.unexpected("an operator or end of input")),
+        }
+    }
+
+    /// Evaluates the expression, looking up each variable with `vars`. Arithmetic is checked, so
+    /// overflow is an error rather than a wrap or panic.
+    pub fn eval<F: Fn(&str) -> Option<i64>>(&self, vars: F) -> Result<i64, EvalError> {
+        self.eval_with(&vars)
+    }
+
+    fn eval_with<F: Fn(&str) -> Option<i64>>(&self, vars: &F) -> Result<i64, EvalError> {
+        match self {
+            Self::Number(n) => Ok(*n),
+            Self::Variable(name) => vars(name).ok_or_else(|| EvalError::UnknownVariable { name: name.clone() }),
+            Self::Neg(operand) => operand.eval_with(vars)?.checked_neg().ok_or(EvalError::Overflow),
+            Self::Binary { op, lhs, rhs } => {
+                let (l, r) = (lhs.eval_with(vars)?, rhs.eval_with(vars)?);
+                let result = match op {
+                    BinOp::Add => l.checked_add(r),
+                    BinOp::Sub => l.checked_sub(r),
+                    BinOp::Mul => l.checked_mul(r),
+                    BinOp::Div | BinOp::Rem if r == 0 => return Err(EvalError::DivisionByZero),
+                    BinOp::Div => l.checked_div(r),
+                    BinOp::Rem => l.checked_rem(r),
+                };
+
+                result.ok_or(EvalError::Overflow)
+            }
+        }
+    }
+
+    /// The names of the variables the expression refers to, sorted and without repeats.
+    pub fn variables(&self) -> Vec<&str> {
+        let mut names = vec![];
+        self.collect_variables(&mut names);
+        names.sort_unstable();
+        names.dedup();
+        names
+    }
+
+    fn collect_variables<'a>(&'a self, names: &mut Vec<&'a str>) {
+        match self {
+            Self::Number(_) => {}
+            Self::Variable(name) => names.push(name),
+            Self::Neg(operand) => operand.collect_variables(names),
+            Self::Binary { lhs, rhs, .. } => {
+                lhs.collect_variables(names);
+                rhs.collect_variables(names);
+            }
+        }
+    }
+
+    fn precedence(&self) -> u8 {
+        match self {
+            Self::Binary { op, .. } => op.precedence(),
+            _ => u8::MAX,
+        }
+    }
+}
+
+impl FromStr for Expr {
+    type Err = ExprError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+/// Writes the expression with only the parentheses it needs, so it parses back to the same tree.
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Number(n) => write!(f, "{n}"),
+            Self::Variable(name) => write!(f, "{name}"),
+            Self::Neg(operand) if operand.precedence() == u8::MAX => write!(f, "-{operand}"),
+            Self::Neg(operand) => write!(f, "-({operand})"),
+            Self::Binary { op, lhs, rhs } => {
+                if lhs.precedence() < op.precedence() {
+                    write!(f, "({lhs})")?;
+                } else {
+                    write!(f, "{lhs}")?;
+                }
+
+                write!(f, " {} ", op.symbol())?;
+
+                // everything is left associative, so an equal-precedence right operand needs them too
+                if rhs.precedence() <= op.precedence() {
+                    write!(f, "({rhs})")
+                } else {
+                    write!(f, "{rhs}")
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Token {
+    Number(i64),
+    Identifier(String),
+    Op(BinOp),
+    Open,
+    Close,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Number(n) => write!(f, "{n}"),
+            Self::Identifier(name) => write!(f, "{name}"),
+            Self::Op(op) => write!(f, "{}", op.symbol()),
+            Self::Open => write!(f, "("),
+            Self::Close => write!(f, ")"),
+        }
+    }
+}
+
+/// Splits `input` into tokens, each with the (character) column it starts at.
+fn tokenize(input: &str) -> Result<Vec<(usize, Token)>, ExprError> {
+    let error = |kind| ExprError { input: input.to_string(), kind };
+    let mut tokens = vec![];
+    let mut chars = input.char_indices().enumerate().peekable();
+
+    while let Some((column, (start, c))) = chars.next() {
+        let token = if c.is_whitespace() {
+            continue;
+        } else if c.is_ascii_digit() || c.is_alphabetic() || c == '_' {
+            let mut end = start + c.len_utf8();
+            while let Some(&(_, (i, next))) = chars.peek() {
+                if !(next.is_alphanumeric() || next == '_') {
+                    break;
+                }
+                end = i + next.len_utf8();
+                chars.next();
+            }
+
+            let word = &input[start..end];
+            if !c.is_ascii_digit() {
+                Token::Identifier(word.to_string())
+            } else if word.bytes().all(|b| b.is_ascii_digit()) {
+                let n = word.parse().map_err(|_| error(ExprErrorKind::NumberTooLarge { column, digits: word.to_string() }))?;
+                Token::Number(n)
+            } else {
+                // the digits before it are ASCII, so this byte offset is also a character count
+                let offset = word.find(|c: char| !c.is_ascii_digit()).unwrap();
+                let found = word[offset..].chars().next().unwrap();
+                return Err(error(ExprErrorKind::UnexpectedChar { column: column + offset, found }));
+            }
+        } else if c == '(' {
+            Token::Open
+        } else if c == ')' {
+            Token::Close
+        } else if let Some(op) = BinOp::from_symbol(c) {
+            Token::Op(op)
+        } else {
+            return Err(error(ExprErrorKind::UnexpectedChar { column, found: c }));
+        };
+
+        tokens.push((column, token));
+    }
+
+    Ok(tokens)
+}
+
+/// A recursive descent parser with one level per precedence.
+struct Parser<'a> {
+    input: &'a str,
+    tokens: Vec<(usize, Token)>,
+    next: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.next).map(|(_, token)| token)
+    }
+
+    fn unexpected(&self, expected: &'static str) -> ExprError {
+        let kind = match self.tokens.get(self.next) {
+            Some((column, token)) => ExprErrorKind::UnexpectedToken { column: *column, found: token.to_string(), expected },
+            None => ExprErrorKind::UnexpectedEnd { expected },
+        };
+
+        ExprError { input: self.input.to_string(), kind }
+    }
+
+    /// Parses a chain of operators of at least `precedence`, with operands parsed by `operand`.
+    fn chain(&mut self, precedence: u8, operand: fn(&mut Self) -> Result<Expr, ExprError>) -> Result<Expr, ExprError> {
+        let mut expr = operand(self)?;
+
+        while let Some(&Token::Op(op)) = self.peek() {
+            if op.precedence() != precedence {
+                break;
+            }
+
+            self.next += 1;
+            let rhs = operand(self)?;
+            expr = Expr::Binary { op, lhs: Box::new(expr), rhs: Box::new(rhs) };
+        }
+
+        Ok(expr)
+    }
+
+    fn sum(&mut self) -> Result<Expr, ExprError> {
+        self.chain(1, Self::product)
+    }
+
+    fn product(&mut self) -> Result<Expr, ExprError> {
+        self.chain(2, Self::unary)
+    }
+
+    fn unary(&mut self) -> Result<Expr, ExprError> {
+        if self.peek() == Some(&Token::Op(BinOp::Sub)) {
+            self.next += 1;
+            return Ok(Expr::Neg(Box::new(self.unary()?)));
+        }
+
+        self.atom()
+    }
+
+    fn atom(&mut self) -> Result<Expr, ExprError> {
+        let expr = match self.peek() {
+            Some(Token::Number(n)) => Expr::Number(*n),
+            Some(Token::Identifier(name)) => Expr::Variable(name.clone()),
+            Some(Token::Open) => {
+                self.next += 1;
+                let inner = self.sum()?;
+                if self.peek() != Some(&Token::Close) {
+                    return Err(self.unexpected("\")\""));
+                }
+                inner
+            }
+            _ => return Err(self.unexpected("a number, variable or \"(\"")),
+        };
+
+        self.next += 1;
+        Ok(expr)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(input: &str) -> Result<i64, EvalError> {
+        Expr::parse(input).unwrap().eval(|name| (name == "old").then_some(5))
+    }
+
+    fn error(input: &str) -> ExprErrorKind {
+        Expr::parse(input).unwrap_err().kind
+    }
+
+    #[test]
+    fn precedence() {
+        assert_eq!(eval("1 + 2 * 3"), Ok(7));
+        assert_eq!(eval("(1 + 2) * 3"), Ok(9));
+        assert_eq!(eval("10 - 6 / 2 % 2"), Ok(9));
+        assert_eq!(eval("old * old + old"), Ok(30));
+        assert_eq!(eval("-old * 2"), Ok(-10));
+        assert_eq!(eval("- -old"), Ok(5));
+    }
+
+    #[test]
+    fn left_associativity() {
+        assert_eq!(eval("10 - 4 - 3"), Ok(3));
+        assert_eq!(eval("64 / 4 / 2"), Ok(8));
+        assert_eq!(eval("100 % 7 % 3"), Ok(2));
+        assert_eq!(eval("10 - (4 - 3)"), Ok(9));
+    }
+
+    #[test]
+    fn integer_division_truncates() {
+        assert_eq!(eval("7 / 2"), Ok(3));
+        assert_eq!(eval("-7 / 2"), Ok(-3));
+        assert_eq!(eval("-7 % 2"), Ok(-1));
+    }
+
+    #[test]
+    fn display_round_trips() {
+        for input in ["old * old", "1 + 2 * 3", "(1 + 2) * 3", "10 - (4 - 3) - 2", "a / (b * c)", "-(x + 1) % 7", "--x", "(((y)))"] {
+            let expr = Expr::parse(input).unwrap();
+            let printed = expr.to_string();
+            assert_eq!(Expr::parse(&printed), Ok(expr), "{input} printed as {printed}");
+        }
+
+        assert_eq!(Expr::parse("(((y)))").unwrap().to_string(), "y");
+        assert_eq!(Expr::parse("(a * b) + (c * d)").unwrap().to_string(), "a * b + c * d");
+        assert_eq!(Expr::parse("a - (b + c)").unwrap().to_string(), "a - (b + c)");
+    }
+
+    #[test]
+    fn variables() {
+        let expr: Expr = "b * a + old - b".parse().unwrap();
+        assert_eq!(expr.variables(), vec!["a", "b", "old"]);
+        assert!(Expr::parse("1 + 2").unwrap().variables().is_empty());
+    }
+
+    #[test]
+    fn parse_error_columns() {
+        assert_eq!(error("2 $ 3"), ExprErrorKind::UnexpectedChar { column: 2, found: '$' });
+        assert_eq!(error("12ab"), ExprErrorKind::UnexpectedChar { column: 2, found: 'a' });
+        assert_eq!(error("old old"), ExprErrorKind::UnexpectedToken { column: 4, found: "old".to_string(), expected: "an operator or end of input" });
+        assert_eq!(error(")"), ExprErrorKind::UnexpectedToken { column: 0, found: ")".to_string(), expected: "a number, variable or \"(\"" });
+        assert_eq!(error("(1 + 2"), ExprErrorKind::UnexpectedEnd { expected: "\")\"" });
+        assert_eq!(error("3 +"), ExprErrorKind::UnexpectedEnd { expected: "a number, variable or \"(\"" });
+        assert_eq!(error(""), ExprErrorKind::UnexpectedEnd { expected: "a number, variable or \"(\"" });
+        assert_eq!(error("99999999999999999999"), ExprErrorKind::NumberTooLarge { column: 0, digits: "99999999999999999999".to_string() });
+    }
+
+    #[test]
+    fn parse_error_columns_count_characters() {
+        assert_eq!(error("été + 1 $"), ExprErrorKind::UnexpectedChar { column: 8, found: '$' });
+        assert_eq!(error("«a»"), ExprErrorKind::UnexpectedChar { column: 0, found: '«' });
+        assert_eq!(error("ü ü"), ExprErrorKind::UnexpectedToken { column: 2, found: "ü".to_string(), expected: "an operator or end of input" });
+        assert_eq!(Expr::parse("é $").unwrap_err().to_string(), "unexpected '$' at column 3 of \"é $\"");
+    }
+
+    #[test]
+    fn evaluation_errors() {
+        assert_eq!(eval("1 / (old - 5)"), Err(EvalError::DivisionByZero));
+        assert_eq!(eval("old % 0"), Err(EvalError::DivisionByZero));
+        assert_eq!(eval("9223372036854775807 + 1"), Err(EvalError::Overflow));
+        assert_eq!(eval("3037000500 * 3037000500"), Err(EvalError::Overflow));
+        assert_eq!(eval("-9223372036854775807 - 2"), Err(EvalError::Overflow));
+        assert_eq!(eval("new + 1"), Err(EvalError::UnknownVariable { name: "new".to_string() }));
+    }
+}
@@ -0,0 +1,481 @@
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, Sub, SubAssign};
+
+/// Numeric types usable as point coordinates.
+pub trait Scalar:
+    Copy + PartialEq + PartialOrd
+    + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> + Div<Output = Self> + Rem<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn abs(self) -> Self;
+    fn signum(self) -> Self;
+}
+
+macro_rules! impl_scalar_signed {
+    ($($t:ty),*) => {$(
+        impl Scalar for $t {
+            const ZERO: Self = 0 as $t;
+            const ONE: Self = 1 as $t;
+
+            fn abs(self) -> Self {
+                <$t>::abs(self)
+            }
+
+            fn signum(self) -> Self {
+                if self > Self::ZERO {
+                    Self::ONE
+                } else if self < Self::ZERO {
+                    Self::ZERO - Self::ONE
+                } else {
+                    Self::ZERO
+                }
+            }
+        }
+    )*};
+}
+
+macro_rules! impl_scalar_unsigned {
+    ($($t:ty),*) => {$(
+        impl Scalar for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+
+            fn abs(self) -> Self {
+                self
+            }
+
+            fn signum(self) -> Self {
+                (self > 0) as $t
+            }
+        }
+    )*};
+}
+
+impl_scalar_signed!(i8, i16, i32, i64, i128, isize, f32, f64);
+impl_scalar_unsigned!(u8, u16, u32, u64, u128, usize);
+
+fn abs_diff<T: Scalar>(a: T, b: T) -> T {
+    if a > b { a - b } else { b - a }
+}
+
+fn max<T: Scalar>(a: T, b: T) -> T {
+    if a > b { a } else { b }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point2<T> {
+    pub x: T,
+    pub y: T,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T> Point2<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Self { x, y }
+    }
+}
+
+impl<T> Point3<T> {
+    pub const fn new(x: T, y: T, z: T) -> Self {
+        Self { x, y, z }
+    }
+}
+
+impl<T: Scalar> Point2<T> {
+    pub const ORIGIN: Self = Self { x: T::ZERO, y: T::ZERO };
+
+    pub fn manhattan(&self, other: &Self) -> T {
+        abs_diff(self.x, other.x) + abs_diff(self.y, other.y)
+    }
+
+    pub fn chebyshev(&self, other: &Self) -> T {
+        max(abs_diff(self.x, other.x), abs_diff(self.y, other.y))
+    }
+
+    /// Each component reduced to -1, 0 or 1; the unit step of a king move towards this offset.
+    pub fn signum(&self) -> Self {
+        Self { x: self.x.signum(), y: self.y.signum() }
+    }
+
+    pub fn abs(&self) -> Self {
+        Self { x: self.x.abs(), y: self.y.abs() }
+    }
+
+    /// The four orthogonally adjacent points.
+    pub fn neighbours4(&self) -> [Self; 4] where T: Neg<Output = T> {
+        Dir4::ALL.map(|dir| *self + dir.delta())
+    }
+
+    /// The eight orthogonally and diagonally adjacent points.
+    pub fn neighbours8(&self) -> [Self; 8] where T: Neg<Output = T> {
+        Dir8::ALL.map(|dir| *self + dir.delta())
+    }
+}
+
+impl<T: Scalar> Point3<T> {
+    pub const ORIGIN: Self = Self { x: T::ZERO, y: T::ZERO, z: T::ZERO };
+
+    pub fn manhattan(&self, other: &Self) -> T {
+        abs_diff(self.x, other.x) + abs_diff(self.y, other.y) + abs_diff(self.z, other.z)
+    }
+
+    pub fn chebyshev(&self, other: &Self) -> T {
+        max(max(abs_diff(self.x, other.x), abs_diff(self.y, other.y)), abs_diff(self.z, other.z))
+    }
+
+    pub fn signum(&self) -> Self {
+        Self { x: self.x.signum(), y: self.y.signum(), z: self.z.signum() }
+    }
+
+    pub fn abs(&self) -> Self {
+        Self { x: self.x.abs(), y: self.y.abs(), z: self.z.abs() }
+    }
+
+    /// The six face-adjacent points.
+    pub fn neighbours6(&self) -> [Self; 6] {
+        let o = T::ONE;
+        [
+            Self::new(self.x + o, self.y, self.z),
+            Self::new(self.x - o, self.y, self.z),
+            Self::new(self.x, self.y + o, self.z),
+            Self::new(self.x, self.y - o, self.z),
+            Self::new(self.x, self.y, self.z + o),
+            Self::new(self.x, self.y, self.z - o),
+        ]
+    }
+}
+
+macro_rules! impl_point_ops {
+    ($point:ident { $($field:ident),+ }) => {
+        impl<T: Scalar> Add for $point<T> {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self {
+                Self { $($field: self.$field + rhs.$field),+ }
+            }
+        }
+
+        impl<T: Scalar> Sub for $point<T> {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self {
+                Self { $($field: self.$field - rhs.$field),+ }
+            }
+        }
+
+        /// Component-wise multiplication.
+        impl<T: Scalar> Mul for $point<T> {
+            type Output = Self;
+
+            fn mul(self, rhs: Self) -> Self {
+                Self { $($field: self.$field * rhs.$field),+ }
+            }
+        }
+
+        impl<T: Scalar> Mul<T> for $point<T> {
+            type Output = Self;
+
+            fn mul(self, rhs: T) -> Self {
+                Self { $($field: self.$field * rhs),+ }
+            }
+        }
+
+        impl<T: Scalar> Div<T> for $point<T> {
+            type Output = Self;
+
+            fn div(self, rhs: T) -> Self {
+                Self { $($field: self.$field / rhs),+ }
+            }
+        }
+
+        impl<T: Scalar + Neg<Output = T>> Neg for $point<T> {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                Self { $($field: -self.$field),+ }
+            }
+        }
+
+        impl<T: Scalar> AddAssign for $point<T> {
+            fn add_assign(&mut self, rhs: Self) {
+                *self = *self + rhs;
+            }
+        }
+
+        impl<T: Scalar> SubAssign for $point<T> {
+            fn sub_assign(&mut self, rhs: Self) {
+                *self = *self - rhs;
+            }
+        }
+
+        impl<T: Scalar> MulAssign for $point<T> {
+            fn mul_assign(&mut self, rhs: Self) {
+                *self = *self * rhs;
+            }
+        }
+
+        impl<T: Scalar> MulAssign<T> for $point<T> {
+            fn mul_assign(&mut self, rhs: T) {
+                *self = *self * rhs;
+            }
+        }
+
+        impl<T: Scalar> DivAssign<T> for $point<T> {
+            fn div_assign(&mut self, rhs: T) {
+                *self = *self / rhs;
+            }
+        }
+    };
+}
+
+impl_point_ops!(Point2 { x, y });
+impl_point_ops!(Point3 { x, y, z });
+
+impl<T> From<(T, T)> for Point2<T> {
+    fn from((x, y): (T, T)) -> Self {
+        Self { x, y }
+    }
+}
+
+impl<T> From<[T; 2]> for Point2<T> {
+    fn from([x, y]: [T; 2]) -> Self {
+        Self { x, y }
+    }
+}
+
+impl<T> From<Point2<T>> for (T, T) {
+    fn from(p: Point2<T>) -> Self {
+        (p.x, p.y)
+    }
+}
+
+impl<T> From<Point2<T>> for [T; 2] {
+    fn from(p: Point2<T>) -> Self {
+        [p.x, p.y]
+    }
+}
+
+impl<T> From<(T, T, T)> for Point3<T> {
+    fn from((x, y, z): (T, T, T)) -> Self {
+        Self { x, y, z }
+    }
+}
+
+impl<T> From<[T; 3]> for Point3<T> {
+    fn from([x, y, z]: [T; 3]) -> Self {
+        Self { x, y, z }
+    }
+}
+
+impl<T> From<Point3<T>> for (T, T, T) {
+    fn from(p: Point3<T>) -> Self {
+        (p.x, p.y, p.z)
+    }
+}
+
+impl<T> From<Point3<T>> for [T; 3] {
+    fn from(p: Point3<T>) -> Self {
+        [p.x, p.y, p.z]
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Point2<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Point3<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {}, {})", self.x, self.y, self.z)
+    }
+}
+
+/// An orthogonal direction. Deltas use screen coordinates, so `Up` is negative y.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Dir4 {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Dir4 {
+    pub const ALL: [Dir4; 4] = [Dir4::Up, Dir4::Right, Dir4::Down, Dir4::Left];
+
+    pub fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 4]
+    }
+
+    pub fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 3) % 4]
+    }
+
+    pub fn reverse(self) -> Self {
+        Self::ALL[(self as usize + 2) % 4]
+    }
+
+    pub fn delta<T: Scalar + Neg<Output = T>>(self) -> Point2<T> {
+        let (o, z) = (T::ONE, T::ZERO);
+        match self {
+            Self::Up => Point2::new(z, -o),
+            Self::Right => Point2::new(o, z),
+            Self::Down => Point2::new(z, o),
+            Self::Left => Point2::new(-o, z),
+        }
+    }
+
+    /// Accepts `UDLR`, `NESW` and `^>v<`, case-insensitively.
+    pub fn from_char(c: char) -> Option<Self> {
+        match c.to_ascii_uppercase() {
+            'U' | 'N' | '^' => Some(Self::Up),
+            'R' | 'E' | '>' => Some(Self::Right),
+            'D' | 'S' | 'V' => Some(Self::Down),
+            'L' | 'W' | '<' => Some(Self::Left),
+            _ => None,
+        }
+    }
+}
+
+/// An orthogonal or diagonal direction, in clockwise order from `N` (negative y).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Dir8 {
+    N,
+    NE,
+    E,
+    SE,
+    S,
+    SW,
+    W,
+    NW,
+}
+
+impl Dir8 {
+    pub const ALL: [Dir8; 8] = [Dir8::N, Dir8::NE, Dir8::E, Dir8::SE, Dir8::S, Dir8::SW, Dir8::W, Dir8::NW];
+
+    /// Turns clockwise by 45 degrees.
+    pub fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 8]
+    }
+
+    /// Turns counter-clockwise by 45 degrees.
+    pub fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 7) % 8]
+    }
+
+    pub fn reverse(self) -> Self {
+        Self::ALL[(self as usize + 4) % 8]
+    }
+
+    pub fn is_diagonal(self) -> bool {
+        self as usize % 2 == 1
+    }
+
+    pub fn delta<T: Scalar + Neg<Output = T>>(self) -> Point2<T> {
+        let (o, z) = (T::ONE, T::ZERO);
+        match self {
+            Self::N => Point2::new(z, -o),
+            Self::NE => Point2::new(o, -o),
+            Self::E => Point2::new(o, z),
+            Self::SE => Point2::new(o, o),
+            Self::S => Point2::new(z, o),
+            Self::SW => Point2::new(-o, o),
+            Self::W => Point2::new(-o, z),
+            Self::NW => Point2::new(-o, -o),
+        }
+    }
+}
+
+impl From<Dir4> for Dir8 {
+    fn from(dir: Dir4) -> Self {
+        Dir8::ALL[dir as usize * 2]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distances() {
+        let (a, b) = (Point2::new(1, -2), Point2::new(-3, 5));
+        assert_eq!(a.manhattan(&b), 11);
+        assert_eq!(a.chebyshev(&b), 7);
+        assert_eq!(Point2::new(3u32, 1).manhattan(&Point2::new(1, 4)), 5);
+        assert_eq!(Point3::new(1, 2, 3).manhattan(&Point3::ORIGIN), 6);
+        assert_eq!(Point3::new(1, -5, 3).chebyshev(&Point3::ORIGIN), 5);
+    }
+
+    #[test]
+    fn signum_and_abs() {
+        assert_eq!(Point2::new(-7, 0).signum(), Point2::new(-1, 0));
+        assert_eq!(Point2::new(4, -9).abs(), Point2::new(4, 9));
+        assert_eq!(Point2::new(-0.5, 2.0).signum(), Point2::new(-1.0, 1.0));
+        assert_eq!(Point2::new(0u8, 3).signum(), Point2::new(0, 1));
+        assert_eq!(Point3::new(2, -2, 0).signum(), Point3::new(1, -1, 0));
+    }
+
+    #[test]
+    fn arithmetic() {
+        let mut p = Point2::new(2, 3);
+        assert_eq!(p + Point2::new(1, 1), Point2::new(3, 4));
+        assert_eq!(p - Point2::new(5, 5), Point2::new(-3, -2));
+        assert_eq!(p * Point2::new(2, -1), Point2::new(4, -3));
+        assert_eq!(p * 3, Point2::new(6, 9));
+        assert_eq!(p / 2, Point2::new(1, 1));
+        assert_eq!(-p, Point2::new(-2, -3));
+
+        p += Point2::new(1, 0);
+        p *= 2;
+        p -= Point2::new(0, 1);
+        assert_eq!(p, Point2::new(6, 5));
+        assert_eq!(Point3::new(1, 2, 3) + Point3::new(1, 1, 1), Point3::new(2, 3, 4));
+    }
+
+    #[test]
+    fn neighbours() {
+        let p = Point2::new(0, 0);
+        assert_eq!(p.neighbours4(), [Point2::new(0, -1), Point2::new(1, 0), Point2::new(0, 1), Point2::new(-1, 0)]);
+        assert_eq!(p.neighbours8().len(), 8);
+        assert!(p.neighbours8().iter().all(|n| n.chebyshev(&p) == 1));
+        assert!(Point3::new(0, 0, 0).neighbours6().iter().all(|n| n.manhattan(&Point3::ORIGIN) == 1));
+    }
+
+    #[test]
+    fn conversions_and_display() {
+        let p: Point2<i32> = (1, 2).into();
+        assert_eq!(p, Point2::from([1, 2]));
+        assert_eq!(<(i32, i32)>::from(p), (1, 2));
+        assert_eq!(<[i32; 3]>::from(Point3::new(1, 2, 3)), [1, 2, 3]);
+        assert_eq!(p.to_string(), "(1, 2)");
+        assert_eq!(Point3::new(1, 2, 3).to_string(), "(1, 2, 3)");
+    }
+
+    #[test]
+    fn directions() {
+        assert_eq!(Dir4::Up.turn_right(), Dir4::Right);
+        assert_eq!(Dir4::Up.turn_left(), Dir4::Left);
+        assert_eq!(Dir4::Left.reverse(), Dir4::Right);
+        assert_eq!(Dir4::Up.delta::<i32>(), Point2::new(0, -1));
+        assert_eq!(Dir4::from_char('v'), Some(Dir4::Down));
+        assert_eq!(Dir4::from_char('w'), Some(Dir4::Left));
+        assert_eq!(Dir4::from_char('x'), None);
+
+        assert_eq!(Dir8::NW.turn_right(), Dir8::N);
+        assert_eq!(Dir8::N.turn_left(), Dir8::NW);
+        assert_eq!(Dir8::NE.reverse(), Dir8::SW);
+        assert!(Dir8::SE.is_diagonal() && !Dir8::S.is_diagonal());
+        assert_eq!(Dir8::SW.delta::<i64>(), Point2::new(-1, 1));
+        for dir in Dir4::ALL {
+            assert_eq!(Dir8::from(dir).delta::<i32>(), dir.delta());
+        }
+    }
+}
@@ -0,0 +1,392 @@
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+/// Offsets to the orthogonal neighbours of a cell, clockwise from up.
+pub const NEIGHBOURS4: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// Offsets to the orthogonal and diagonal neighbours of a cell, clockwise from up.
+pub const NEIGHBOURS8: [(isize, isize); 8] = [(0, -1), (1, -1), (1, 0), (1, 1), (0, 1), (-1, 1), (-1, 0), (-1, -1)];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GridError {
+    Empty,
+    RaggedRow { row: usize, expected: usize, found: usize },
+    InvalidChar { x: usize, y: usize, c: char },
+    SizeMismatch { width: usize, height: usize, len: usize },
+}
+
+impl fmt::Display for GridError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "grid is empty"),
+            Self::RaggedRow { row, expected, found } => write!(f, "row {row} has {found} cells, expected {expected}"),
+            Self::InvalidChar { x, y, c } => write!(f, "unexpected character '{c}' at ({x}, {y})"),
+            Self::SizeMismatch { width, height, len } => write!(f, "{len} cells cannot fill a {width}x{height} grid"),
+        }
+    }
+}
+
+impl std::error::Error for GridError {}
+
+/// A dense, row-major 2D grid indexed by `(x, y)`, with `(0, 0)` at the top left.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self {
+        Self { width, height, cells: vec![fill; width * height] }
+    }
+}
+
+impl<T: Default> Grid<T> {
+    pub fn with_default(width: usize, height: usize) -> Self {
+        Self::from_fn(width, height, |_, _| T::default())
+    }
+}
+
+impl Grid<char> {
+    pub fn parse_chars(input: &str) -> Result<Self, GridError> {
+        Self::parse(input, Some)
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(usize, usize) -> T) -> Self {
+        let cells = (0..height).flat_map(|y| (0..width).map(move |x| (x, y))).map(|(x, y)| f(x, y)).collect();
+        Self { width, height, cells }
+    }
+
+    pub fn from_vec(width: usize, height: usize, cells: Vec<T>) -> Result<Self, GridError> {
+        if cells.len() != width * height {
+            return Err(GridError::SizeMismatch { width, height, len: cells.len() });
+        }
+
+        Ok(Self { width, height, cells })
+    }
+
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Self, GridError> {
+        let width = rows.first().ok_or(GridError::Empty)?.len();
+        let height = rows.len();
+
+        let mut cells = Vec::with_capacity(width * height);
+        for (row, values) in rows.into_iter().enumerate() {
+            if values.len() != width {
+                return Err(GridError::RaggedRow { row, expected: width, found: values.len() });
+            }
+            cells.extend(values);
+        }
+
+        Ok(Self { width, height, cells })
+    }
+
+    /// Parses a character map, one row per line. Rows must all be the same length, ignoring
+    /// trailing `\r`; returning `None` from `f` rejects a character.
+    pub fn parse(input: &str, mut f: impl FnMut(char) -> Option<T>) -> Result<Self, GridError> {
+        let mut rows = vec![];
+        for (y, line) in input.lines().enumerate() {
+            let row = line.trim_end_matches('\r').chars().enumerate()
+                .map(|(x, c)| f(c).ok_or(GridError::InvalidChar { x, y, c }))
+                .collect::<Result<Vec<T>, _>>()?;
+            rows.push(row);
+        }
+
+        Self::from_rows(rows)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn in_bounds(&self, x: isize, y: isize) -> bool {
+        x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        (x < self.width && y < self.height).then(|| &self.cells[y * self.width + x])
+    }
+
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        (x < self.width && y < self.height).then(|| &mut self.cells[y * self.width + x])
+    }
+
+    /// Bounds-checked access with signed coordinates, convenient when stepping by an offset.
+    pub fn get_signed(&self, x: isize, y: isize) -> Option<&T> {
+        self.in_bounds(x, y).then(|| &self.cells[y as usize * self.width + x as usize])
+    }
+
+    /// Access that wraps around the edges, as on a torus. `None` only if the grid is empty.
+    pub fn get_wrapped(&self, x: isize, y: isize) -> Option<&T> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let x = x.rem_euclid(self.width as isize) as usize;
+        let y = y.rem_euclid(self.height as isize) as usize;
+        Some(&self.cells[y * self.width + x])
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, value: T) -> Option<T> {
+        self.get_mut(x, y).map(|cell| std::mem::replace(cell, value))
+    }
+
+    /// The in-bounds cell `(dx, dy)` away from `(x, y)`.
+    pub fn offset(&self, x: usize, y: usize, (dx, dy): (isize, isize)) -> Option<(usize, usize)> {
+        let (nx, ny) = (x as isize + dx, y as isize + dy);
+        self.in_bounds(nx, ny).then_some((nx as usize, ny as usize))
+    }
+
+    pub fn neighbours4(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        NEIGHBOURS4.iter().filter_map(move |&d| self.offset(x, y, d))
+    }
+
+    pub fn neighbours8(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        NEIGHBOURS8.iter().filter_map(move |&d| self.offset(x, y, d))
+    }
+
+    /// Orthogonal neighbours, wrapping around the edges. Yields nothing on an empty grid.
+    pub fn neighbours4_wrapped(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        NEIGHBOURS4.iter().filter_map(move |&d| self.offset_wrapped(x, y, d))
+    }
+
+    /// Orthogonal and diagonal neighbours, wrapping around the edges. Yields nothing on an empty grid.
+    pub fn neighbours8_wrapped(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        NEIGHBOURS8.iter().filter_map(move |&d| self.offset_wrapped(x, y, d))
+    }
+
+    fn offset_wrapped(&self, x: usize, y: usize, (dx, dy): (isize, isize)) -> Option<(usize, usize)> {
+        (!self.is_empty()).then(|| (
+            (x as isize + dx).rem_euclid(self.width as isize) as usize,
+            (y as isize + dy).rem_euclid(self.height as isize) as usize,
+        ))
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn row_mut(&mut self, y: usize) -> &mut [T] {
+        &mut self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn column(&self, x: usize) -> impl DoubleEndedIterator<Item = &T> + ExactSizeIterator {
+        assert!(x < self.width, "column {x} out of bounds for width {}", self.width);
+        self.cells[x..].iter().step_by(self.width)
+    }
+
+    pub fn rows(&self) -> impl DoubleEndedIterator<Item = &[T]> + ExactSizeIterator {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn columns(&self) -> impl DoubleEndedIterator<Item = impl DoubleEndedIterator<Item = &T>> {
+        (0..self.width).map(move |x| self.column(x))
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.cells.iter_mut()
+    }
+
+    /// Every cell alongside its position, in row-major order.
+    pub fn enumerate(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    pub fn position(&self, mut pred: impl FnMut(&T) -> bool) -> Option<(usize, usize)> {
+        let i = self.cells.iter().position(&mut pred)?;
+        Some((i % self.width, i / self.width))
+    }
+
+    pub fn map<U>(&self, mut f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid { width: self.width, height: self.height, cells: self.cells.iter().map(&mut f).collect() }
+    }
+
+    pub fn into_vec(self) -> Vec<T> {
+        self.cells
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn transpose(&self) -> Self {
+        Self::from_fn(self.height, self.width, |x, y| self[(y, x)].clone())
+    }
+
+    /// Rotates a quarter turn clockwise.
+    pub fn rotate_cw(&self) -> Self {
+        Self::from_fn(self.height, self.width, |x, y| self[(y, self.height - 1 - x)].clone())
+    }
+
+    /// Rotates a quarter turn counter-clockwise.
+    pub fn rotate_ccw(&self) -> Self {
+        Self::from_fn(self.height, self.width, |x, y| self[(self.width - 1 - y, x)].clone())
+    }
+
+    pub fn rotate_180(&self) -> Self {
+        Self::from_fn(self.width, self.height, |x, y| self[(self.width - 1 - x, self.height - 1 - y)].clone())
+    }
+
+    /// Mirrors left to right.
+    pub fn flip_horizontal(&self) -> Self {
+        Self::from_fn(self.width, self.height, |x, y| self[(self.width - 1 - x, y)].clone())
+    }
+
+    /// Mirrors top to bottom.
+    pub fn flip_vertical(&self) -> Self {
+        Self::from_fn(self.width, self.height, |x, y| self[(x, self.height - 1 - y)].clone())
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        self.get(x, y).unwrap_or_else(|| panic!("({x}, {y}) out of bounds for {}x{} grid", self.width, self.height))
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(x, y).unwrap_or_else(|| panic!("({x}, {y}) out of bounds for {width}x{height} grid"))
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Grid<char> {
+        Grid::parse_chars("abc\ndef").unwrap()
+    }
+
+    #[test]
+    fn parses_rows_of_text() {
+        let grid = sample();
+        assert_eq!((grid.width(), grid.height(), grid.len()), (3, 2, 6));
+        assert_eq!(grid.row(1), &['d', 'e', 'f']);
+        assert_eq!(grid.to_string(), "abc\ndef");
+        assert_eq!(Grid::parse_chars("ab\r\ncd\r\n").unwrap(), Grid::parse_chars("ab\ncd").unwrap());
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(Grid::parse_chars(""), Err(GridError::Empty));
+        assert_eq!(Grid::parse_chars("abc\nde"), Err(GridError::RaggedRow { row: 1, expected: 3, found: 2 }));
+        assert_eq!(Grid::parse("01\n2x", |c| c.to_digit(10)), Err(GridError::InvalidChar { x: 1, y: 1, c: 'x' }));
+        assert_eq!(Grid::from_vec(2, 2, vec![1, 2, 3]), Err(GridError::SizeMismatch { width: 2, height: 2, len: 3 }));
+    }
+
+    #[test]
+    fn bounds() {
+        let grid = sample();
+        assert!(grid.in_bounds(0, 0) && grid.in_bounds(2, 1));
+        assert!(!grid.in_bounds(-1, 0) && !grid.in_bounds(0, -1) && !grid.in_bounds(3, 0) && !grid.in_bounds(0, 2));
+        assert_eq!(grid.get_signed(-1, 0), None);
+        assert_eq!(grid.get_signed(1, 1), Some(&'e'));
+        assert_eq!(grid.offset(0, 0, (-1, 0)), None);
+        assert_eq!(grid.offset(0, 0, (2, 1)), Some((2, 1)));
+    }
+
+    #[test]
+    fn get_and_get_mut() {
+        let mut grid = sample();
+        assert_eq!(grid.get(2, 0), Some(&'c'));
+        assert_eq!(grid.get(3, 0), None);
+        assert_eq!(grid.get(0, 2), None);
+
+        *grid.get_mut(1, 0).unwrap() = 'x';
+        assert!(grid.get_mut(0, 5).is_none());
+        assert_eq!(grid.set(0, 1, 'y'), Some('d'));
+        assert_eq!(grid.set(9, 9, 'z'), None);
+        assert_eq!(grid.to_string(), "axc\nyef");
+        assert_eq!(grid[(1, 0)], 'x');
+    }
+
+    #[test]
+    #[should_panic(expected = "out of bounds")]
+    fn index_out_of_bounds_panics() {
+        let _ = sample()[(3, 0)];
+    }
+
+    #[test]
+    fn neighbours() {
+        let grid = Grid::new(3, 3, 0);
+        assert_eq!(grid.neighbours4(0, 0).collect::<Vec<_>>(), vec![(1, 0), (0, 1)]);
+        assert_eq!(grid.neighbours4(1, 1).count(), 4);
+        assert_eq!(grid.neighbours8(0, 0).collect::<Vec<_>>(), vec![(1, 0), (1, 1), (0, 1)]);
+        assert_eq!(grid.neighbours8(1, 1).count(), 8);
+        assert_eq!(grid.neighbours8(2, 2).collect::<Vec<_>>(), vec![(2, 1), (1, 2), (1, 1)]);
+    }
+
+    #[test]
+    fn wrapped_access() {
+        let grid = sample();
+        assert_eq!(grid.get_wrapped(-1, 0), Some(&'c'));
+        assert_eq!(grid.get_wrapped(3, -1), Some(&'d'));
+        assert_eq!(grid.get_wrapped(-7, 4), Some(&'c'));
+
+        assert_eq!(grid.neighbours4_wrapped(0, 0).collect::<Vec<_>>(), vec![(0, 1), (1, 0), (0, 1), (2, 0)]);
+        assert_eq!(grid.neighbours8_wrapped(2, 1).count(), 8);
+        assert!(grid.neighbours8_wrapped(2, 1).all(|(x, y)| grid.get(x, y).is_some()));
+    }
+
+    #[test]
+    fn wrapped_access_on_empty_grid() {
+        let grid: Grid<u8> = Grid::new(0, 0, 0);
+        assert!(grid.is_empty());
+        assert_eq!(grid.get_wrapped(1, 1), None);
+        assert_eq!(grid.neighbours4_wrapped(0, 0).count(), 0);
+        assert_eq!(grid.neighbours8_wrapped(0, 0).count(), 0);
+    }
+
+    #[test]
+    fn transforms() {
+        let grid = sample();
+        assert_eq!(grid.transpose().to_string(), "ad\nbe\ncf");
+        assert_eq!(grid.rotate_cw().to_string(), "da\neb\nfc");
+        assert_eq!(grid.rotate_ccw().to_string(), "cf\nbe\nad");
+        assert_eq!(grid.rotate_180().to_string(), "fed\ncba");
+        assert_eq!(grid.flip_horizontal().to_string(), "cba\nfed");
+        assert_eq!(grid.flip_vertical().to_string(), "def\nabc");
+        assert_eq!(grid.column(1).collect::<String>(), "be");
+        assert_eq!(grid.position(|&c| c == 'e'), Some((1, 1)));
+    }
+}
@@ -1,12 +1,106 @@
-pub struct AocEntry {
+pub mod bench;
+pub mod cli;
+pub mod cycle;
+pub mod expr;
+pub mod geom;
+pub mod grid;
+pub mod math;
+pub mod memory;
+pub mod ocr;
+pub mod parse;
+pub mod ranges;
+pub mod registry;
+pub mod search;
+pub mod tree;
+pub mod window;
+
+pub use registry::{registry, Registry};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EntryKey {
+    pub year: u32,
     pub day: u32,
     pub part: u32,
+    pub variant: Option<&'static str>,
+}
+
+impl EntryKey {
+    pub const fn new(year: u32, day: u32, part: u32, variant: Option<&'static str>) -> Self {
+        Self { year, day, part, variant }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct EntryMeta {
+    pub name: &'static str,
+    pub module_path: &'static str,
+    pub file: &'static str,
+    pub line: u32,
+    pub doc: Option<&'static str>,
+    pub title: Option<&'static str>,
+}
+
+impl EntryMeta {
+    pub const fn new(name: &'static str, module_path: &'static str) -> Self {
+        Self { name, module_path, file: "", line: 0, doc: None, title: None }
+    }
+
+    pub const fn with_source(mut self, file: &'static str, line: u32) -> Self {
+        self.file = file;
+        self.line = line;
+        self
+    }
+
+    pub const fn with_doc(mut self, doc: &'static str) -> Self {
+        self.doc = Some(doc);
+        self
+    }
+
+    pub const fn with_title(mut self, title: &'static str) -> Self {
+        self.title = Some(title);
+        self
+    }
+
+    /// The first line of the doc comment, if any.
+    pub fn summary(&self) -> Option<&'static str> {
+        self.doc.and_then(|doc| doc.lines().map(str::trim).find(|line| !line.is_empty()))
+    }
+
+    /// `file:line` of the solution function, or the module path if unknown.
+    pub fn location(&self) -> String {
+        if self.file.is_empty() {
+            self.module_path.to_string()
+        } else {
+            format!("{}:{}", self.file, self.line)
+        }
+    }
+}
+
+pub struct AocEntry {
+    pub key: EntryKey,
+    pub meta: EntryMeta,
     pub executor: fn(String) -> String,
 }
 
 impl AocEntry {
-    pub const fn new(day: u32, part: u32, executor: fn(String) -> String) -> Self {
-        Self { day, part, executor }
+    pub const fn new(key: EntryKey, meta: EntryMeta, executor: fn(String) -> String) -> Self {
+        Self { key, meta, executor }
+    }
+
+    pub fn year(&self) -> u32 {
+        self.key.year
+    }
+
+    pub fn day(&self) -> u32 {
+        self.key.day
+    }
+
+    pub fn part(&self) -> u32 {
+        self.key.part
+    }
+
+    pub fn variant(&self) -> Option<&'static str> {
+        self.key.variant
     }
 
     pub fn execute(&self, input: String) -> String {
@@ -16,40 +110,54 @@ impl AocEntry {
 
 inventory::collect!(AocEntry);
 
-pub fn __load_test_data(package_dir: &str, day: u32) -> String {
-    std::fs::read_to_string(format!("{}/input/day{}.txt", package_dir, day)).unwrap()
-}
-
-pub fn __main(package_dir: &str) {
-    let args: Vec<String> = std::env::args().collect();
-    if args.len() < 3 {
-        println!("Usage: {} <day> <part>", args[0]);
-        return;
+/// Guesses the puzzle year from a package name ending in digits, e.g. `aoc22` -> 2022.
+/// Returns 0 if the name has no trailing digits.
+pub const fn __infer_year(package_name: &str) -> u32 {
+    let bytes = package_name.as_bytes();
+    let mut start = bytes.len();
+    while start > 0 && bytes[start - 1].is_ascii_digit() {
+        start -= 1;
     }
 
-    let day = args[1].parse::<u32>().unwrap();
-    let part = args[2].parse::<u32>().unwrap();
-    let input = __load_test_data(package_dir, day);
-
-    let mut output: Option<String> = None;
-    for entry in inventory::iter::<AocEntry> {
-        if entry.day == day && entry.part == part {
-            output = Some(entry.execute(input));
-            break;
-        }
+    let mut year = 0u32;
+    let mut i = start;
+    while i < bytes.len() {
+        year = year * 10 + (bytes[i] - b'0') as u32;
+        i += 1;
     }
 
-    if let Some(output) = output {
-        println!("==================== Output ====================");
-        println!("{output}");
+    if bytes.len() - start == 2 {
+        2000 + year
     } else {
-        println!("No entry found for day {day} part {part}");
+        year
     }
 }
 
+pub fn __load_test_data(package_dir: &str, day: u32) -> String {
+    std::fs::read_to_string(format!("{}/input/day{}.txt", package_dir, day)).unwrap()
+}
+
+pub fn __main(package_dir: &str) {
+    std::process::exit(cli::main(package_dir));
+}
+
 #[macro_export]
 macro_rules! add_entry {
     ($day:expr, $part:expr, $executor:expr) => {
-        ::inventory::submit!(::aoclib::AocEntry::new($day, $part, $executor));
+        ::aoclib::add_entry!(
+            year = ::aoclib::__infer_year(env!("CARGO_PKG_NAME")),
+            day = $day,
+            part = $part,
+            variant = None,
+            meta = ::aoclib::EntryMeta::new(stringify!($executor), module_path!()).with_source(file!(), line!()),
+            executor = $executor
+        );
+    };
+    (year = $year:expr, day = $day:expr, part = $part:expr, variant = $variant:expr, meta = $meta:expr, executor = $executor:expr) => {
+        ::inventory::submit!(::aoclib::AocEntry::new(
+            ::aoclib::EntryKey::new($year, $day, $part, $variant),
+            $meta,
+            $executor,
+        ));
     };
 }
@@ -0,0 +1,401 @@
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+/// Primitive integer types, converted through `i128` so intermediate products can't overflow.
+pub trait Integer: Copy + Ord + fmt::Debug {
+    fn to_i128(self) -> i128;
+    /// `None` if `value` doesn't fit.
+    fn from_i128(value: i128) -> Option<Self>;
+}
+
+macro_rules! impl_integer {
+    ($($t:ty),*) => {$(
+        impl Integer for $t {
+            fn to_i128(self) -> i128 {
+                self as i128
+            }
+
+            fn from_i128(value: i128) -> Option<Self> {
+                Self::try_from(value).ok()
+            }
+        }
+    )*};
+}
+
+impl_integer!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, usize);
+
+fn gcd_i128(mut a: i128, mut b: i128) -> i128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a.abs()
+}
+
+/// The greatest common divisor, always non-negative. `gcd(0, 0)` is 0.
+///
+/// Panics if the result doesn't fit in `T`, which only happens for `gcd(MIN, 0)` and
+/// `gcd(MIN, MIN)` of a signed type.
+pub fn gcd<T: Integer>(a: T, b: T) -> T {
+    T::from_i128(gcd_i128(a.to_i128(), b.to_i128())).expect("gcd does not fit in the input type")
+}
+
+/// The least common multiple, or `None` if it doesn't fit in `T`. `lcm(0, x)` is 0.
+pub fn lcm<T: Integer>(a: T, b: T) -> Option<T> {
+    T::from_i128(lcm_i128(a.to_i128(), b.to_i128())?)
+}
+
+fn lcm_i128(a: i128, b: i128) -> Option<i128> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+
+    (a / gcd_i128(a, b)).checked_mul(b).map(i128::abs)
+}
+
+/// The gcd of every value; 0 for an empty slice. Panics like [`gcd`] when every value is 0 or `MIN`.
+pub fn gcd_all<T: Integer>(values: &[T]) -> T {
+    let result = values.iter().fold(0, |acc, value| gcd_i128(acc, value.to_i128()));
+    T::from_i128(result).expect("gcd does not fit in the input type")
+}
+
+/// The lcm of every value, or `None` if it doesn't fit in `T`; 1 for an empty slice.
+pub fn lcm_all<T: Integer>(values: &[T]) -> Option<T> {
+    let result = values.iter().try_fold(1, |acc, value| lcm_i128(acc, value.to_i128()))?;
+    T::from_i128(result)
+}
+
+/// Returns `(g, x, y)` such that `a * x + b * y == g == gcd(a, b)`.
+///
+/// Panics in the same cases as [`gcd`]. The coefficients are at most `|b / g|` and `|a / g|`, so
+/// they always fit once `g` does.
+pub fn extended_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+    let (g, x, y) = extended_gcd_i128(a as i128, b as i128);
+    let fit = |value: i128| i64::try_from(value).expect("gcd does not fit in the input type");
+    (fit(g), fit(x), fit(y))
+}
+
+fn extended_gcd_i128(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1i128, 0i128);
+    let (mut old_y, mut y) = (0i128, 1i128);
+
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+
+    if old_r < 0 {
+        (old_r, old_x, old_y) = (-old_r, -old_x, -old_y);
+    }
+
+    (old_r, old_x, old_y)
+}
+
+/// The `x` in `0..modulus` with `a * x ≡ 1`, or `None` if `a` and `modulus` aren't coprime.
+pub fn mod_inverse(a: i64, modulus: i64) -> Option<i64> {
+    assert!(modulus > 0, "modulus must be positive");
+
+    let (g, x, _) = extended_gcd(a.rem_euclid(modulus), modulus);
+    (g == 1).then(|| x.rem_euclid(modulus))
+}
+
+/// `base` to the power of `exp`, modulo `modulus`, by repeated squaring.
+pub fn mod_pow(base: i64, mut exp: u64, modulus: i64) -> i64 {
+    assert!(modulus > 0, "modulus must be positive");
+
+    let m = modulus as i128;
+    let mut base = (base as i128).rem_euclid(m);
+    let mut result = 1 % m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base % m;
+        }
+        base = base * base % m;
+        exp >>= 1;
+    }
+
+    result as i64
+}
+
+/// Solves the system `x ≡ residue (mod modulus)` for each pair, which need not be coprime.
+/// Returns the smallest non-negative `x` and the combined modulus (the lcm of all moduli), or
+/// `None` if the congruences contradict each other or the combined modulus overflows `i64`.
+pub fn crt(congruences: &[(i64, i64)]) -> Option<(i64, i64)> {
+    let mut x: i128 = 0;
+    let mut m: i128 = 1;
+
+    for &(residue, modulus) in congruences {
+        assert!(modulus > 0, "modulus must be positive");
+        let (r, n) = ((residue as i128).rem_euclid(modulus as i128), modulus as i128);
+
+        // x + m * k ≡ r (mod n)  =>  m * k ≡ r - x (mod n)
+        let g = gcd_i128(m, n);
+        let diff = r - x;
+        if diff % g != 0 {
+            return None;
+        }
+
+        let n_g = n / g;
+        let (_, inv, _) = extended_gcd(((m / g) % n_g) as i64, n_g as i64);
+        let k = ((diff / g) % n_g * inv as i128).rem_euclid(n_g);
+
+        let combined = m.checked_mul(n_g).filter(|&c| c <= i64::MAX as i128)?;
+        x = (x + m * k).rem_euclid(combined);
+        m = combined;
+    }
+
+    Some((x as i64, m as i64))
+}
+
+/// An integer modulo `M`, always kept in `0..M`. `M` must be at least 2, which is checked at
+/// compile time.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ModInt<const M: u64>(u64);
+
+impl<const M: u64> ModInt<M> {
+    pub const MODULUS: u64 = M;
+
+    const VALID_MODULUS: () = assert!(M > 1, "ModInt modulus must be at least 2");
+
+    pub fn new(value: i64) -> Self {
+        let () = Self::VALID_MODULUS;
+        Self((value as i128).rem_euclid(M as i128) as u64)
+    }
+
+    pub fn value(self) -> u64 {
+        self.0
+    }
+
+    pub fn pow(self, mut exp: u64) -> Self {
+        let (mut base, mut result) = (self, Self::from(1));
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result *= base;
+            }
+            base *= base;
+            exp >>= 1;
+        }
+
+        result
+    }
+
+    /// The multiplicative inverse, or `None` if the value isn't coprime with `M`.
+    pub fn inverse(self) -> Option<Self> {
+        // in i128 so moduli above i64::MAX work too
+        let (g, x, _) = extended_gcd_i128(self.0 as i128, M as i128);
+        (g == 1).then(|| Self(x.rem_euclid(M as i128) as u64))
+    }
+}
+
+impl<const M: u64> Default for ModInt<M> {
+    fn default() -> Self {
+        Self::from(0)
+    }
+}
+
+impl<const M: u64> From<u64> for ModInt<M> {
+    fn from(value: u64) -> Self {
+        let () = Self::VALID_MODULUS;
+        Self(value % M)
+    }
+}
+
+impl<const M: u64> Add for ModInt<M> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self(((self.0 as u128 + rhs.0 as u128) % M as u128) as u64)
+    }
+}
+
+impl<const M: u64> Sub for ModInt<M> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self(((self.0 as u128 + M as u128 - rhs.0 as u128) % M as u128) as u64)
+    }
+}
+
+impl<const M: u64> Mul for ModInt<M> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self((self.0 as u128 * rhs.0 as u128 % M as u128) as u64)
+    }
+}
+
+/// Multiplies by the inverse of `rhs`; panics if it has none.
+impl<const M: u64> Div for ModInt<M> {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self {
+        let inverse = rhs.inverse().unwrap_or_else(|| panic!("{} has no inverse modulo {}", rhs.0, M));
+        Mul::mul(self, inverse)
+    }
+}
+
+impl<const M: u64> Neg for ModInt<M> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self(0) - self
+    }
+}
+
+impl<const M: u64> AddAssign for ModInt<M> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<const M: u64> SubAssign for ModInt<M> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<const M: u64> MulAssign for ModInt<M> {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl<const M: u64> DivAssign for ModInt<M> {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
+    }
+}
+
+impl<const M: u64> fmt::Display for ModInt<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl<const M: u64> fmt::Debug for ModInt<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (mod {})", self.0, M)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gcd_and_lcm() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(-12, 18), 6);
+        assert_eq!(gcd(0, 0), 0);
+        assert_eq!(gcd(0u8, 7), 7);
+        assert_eq!(lcm(4, 6), Some(12));
+        assert_eq!(lcm(-4, 6), Some(12));
+        assert_eq!(lcm(0, 5), Some(0));
+        assert_eq!(lcm(200u8, 3), None);
+    }
+
+    #[test]
+    fn gcd_and_lcm_of_many() {
+        assert_eq!(gcd_all(&[12, 18, 30]), 6);
+        assert_eq!(gcd_all::<i32>(&[]), 0);
+        assert_eq!(lcm_all(&[2, 3, 5, 7, 11, 13, 17, 19]), Some(9_699_690i64));
+        assert_eq!(lcm_all::<u32>(&[]), Some(1));
+        assert_eq!(lcm_all(&[i64::MAX, i64::MAX - 1]), None);
+    }
+
+    #[test]
+    fn extended_gcd_bezout() {
+        for (a, b) in [(240, 46), (-7, 3), (0, 5), (17, 0), (-12, -18)] {
+            let (g, x, y) = extended_gcd(a, b);
+            assert_eq!(g, gcd(a, b), "gcd({a}, {b})");
+            assert_eq!(a * x + b * y, g, "bezout for ({a}, {b})");
+        }
+    }
+
+    #[test]
+    fn extended_gcd_of_min() {
+        for (a, b) in [(i64::MIN, 1), (i64::MIN, -1), (i64::MIN, 6), (i64::MIN, i64::MAX)] {
+            let (g, x, y) = extended_gcd(a, b);
+            assert_eq!(g, gcd(a, b), "gcd({a}, {b})");
+            assert_eq!(a as i128 * x as i128 + b as i128 * y as i128, g as i128, "bezout for ({a}, {b})");
+        }
+        assert_eq!(gcd(i64::MIN, 6), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "gcd does not fit")]
+    fn gcd_of_min_and_zero_panics() {
+        gcd(i64::MIN, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "gcd does not fit")]
+    fn extended_gcd_of_min_and_zero_panics() {
+        extended_gcd(i64::MIN, 0);
+    }
+
+    #[test]
+    fn inverses_and_powers() {
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(6, 9), None);
+        assert_eq!(mod_pow(2, 10, 1000), 24);
+        assert_eq!(mod_pow(-2, 3, 7), 6);
+        assert_eq!(mod_pow(5, 0, 1), 0);
+        assert_eq!(mod_pow(3, 1_000_000_006, 1_000_000_007), 1);
+    }
+
+    #[test]
+    fn chinese_remainder() {
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt(&[(1, 4), (3, 6)]), Some((9, 12)));
+        assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+        assert_eq!(crt(&[(-1, 5)]), Some((4, 5)));
+        assert_eq!(crt(&[]), Some((0, 1)));
+        assert_eq!(crt(&[(0, i64::MAX), (1, i64::MAX - 1)]), None);
+    }
+
+    type M7 = ModInt<7>;
+
+    #[test]
+    fn mod_int_arithmetic() {
+        let (a, b) = (M7::new(5), M7::new(-4));
+        assert_eq!(b.value(), 3);
+        assert_eq!((a + b).value(), 1);
+        assert_eq!((b - a).value(), 5);
+        assert_eq!((a * b).value(), 1);
+        assert_eq!((a / b).value(), 4);
+        assert_eq!((-a).value(), 2);
+        assert_eq!(a.pow(6), M7::from(1));
+        assert_eq!(M7::default().value(), 0);
+        assert_eq!(M7::from(15).to_string(), "1");
+        assert_eq!(format!("{:?}", a), "5 (mod 7)");
+
+        let mut c = a;
+        c += b;
+        c *= a;
+        c -= M7::new(1);
+        c /= M7::new(2);
+        assert_eq!(c.value(), 2);
+    }
+
+    #[test]
+    fn mod_int_inverse() {
+        assert_eq!(M7::new(3).inverse(), Some(M7::new(5)));
+        assert_eq!(M7::new(0).inverse(), None);
+        assert_eq!(ModInt::<12>::new(4).inverse(), None);
+
+        // a modulus above i64::MAX, where an i64 round trip would go wrong
+        const BIG: u64 = u64::MAX - 58;
+        let x = ModInt::<BIG>::from(u64::MAX - 100);
+        assert_eq!(x * x.inverse().unwrap(), ModInt::from(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "has no inverse")]
+    fn mod_int_division_by_non_invertible_panics() {
+        let _ = ModInt::<12>::new(1) / ModInt::<12>::new(6);
+    }
+}
@@ -0,0 +1,167 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AllocStats {
+    pub allocations: u64,
+    pub bytes_allocated: u64,
+    pub peak_bytes: u64,
+}
+
+/// Wraps the system allocator and counts every allocation made through it.
+/// Installed as the global allocator when the `alloc-stats` feature is enabled.
+pub struct CountingAllocator;
+
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+static BYTES_ALLOCATED: AtomicU64 = AtomicU64::new(0);
+static LIVE_BYTES: AtomicU64 = AtomicU64::new(0);
+static PEAK_BYTES: AtomicU64 = AtomicU64::new(0);
+
+#[cfg(feature = "alloc-stats")]
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+impl CountingAllocator {
+    fn record_alloc(size: usize) {
+        let size = size as u64;
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        BYTES_ALLOCATED.fetch_add(size, Ordering::Relaxed);
+        let live = LIVE_BYTES.fetch_add(size, Ordering::Relaxed) + size;
+        PEAK_BYTES.fetch_max(live, Ordering::Relaxed);
+    }
+
+    fn record_dealloc(size: usize) {
+        LIVE_BYTES.fetch_sub(size as u64, Ordering::Relaxed);
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            Self::record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            Self::record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        Self::record_dealloc(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            Self::record_dealloc(layout.size());
+            Self::record_alloc(new_size);
+        }
+        new_ptr
+    }
+}
+
+/// Whether allocation tracking was compiled in.
+pub const fn enabled() -> bool {
+    cfg!(feature = "alloc-stats")
+}
+
+/// Runs `f` and reports what it allocated, or `None` without the `alloc-stats` feature.
+/// Peak bytes are measured relative to what was already live when `f` started.
+pub fn measure<R>(f: impl FnOnce() -> R) -> (R, Option<AllocStats>) {
+    if !enabled() {
+        return (f(), None);
+    }
+
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let bytes_allocated = BYTES_ALLOCATED.load(Ordering::Relaxed);
+    let live = LIVE_BYTES.load(Ordering::Relaxed);
+    PEAK_BYTES.store(live, Ordering::Relaxed);
+
+    let result = f();
+
+    let stats = AllocStats {
+        allocations: ALLOCATIONS.load(Ordering::Relaxed) - allocations,
+        bytes_allocated: BYTES_ALLOCATED.load(Ordering::Relaxed) - bytes_allocated,
+        peak_bytes: PEAK_BYTES.load(Ordering::Relaxed).saturating_sub(live),
+    };
+
+    (result, Some(stats))
+}
+
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes}B");
+    }
+
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    // compare what will be printed, so 1023.999KiB moves up to 1.00MiB rather than showing as 1024.00KiB
+    while (value * 100.0).round() >= 1024.0 * 100.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    format!("{:.2}{}", value, UNITS[unit])
+}
+
+impl std::fmt::Display for AllocStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} allocations, {} allocated, {} peak", self.allocations, format_bytes(self.bytes_allocated), format_bytes(self.peak_bytes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_bytes_unit_boundaries() {
+        const KIB: u64 = 1024;
+        assert_eq!(format_bytes(0), "0B");
+        assert_eq!(format_bytes(KIB - 1), "1023B");
+        assert_eq!(format_bytes(KIB), "1.00KiB");
+        assert_eq!(format_bytes(KIB + KIB / 2), "1.50KiB");
+        assert_eq!(format_bytes(KIB * KIB - 1), "1.00MiB");
+        assert_eq!(format_bytes(KIB * KIB - 6), "1023.99KiB");
+        assert_eq!(format_bytes(KIB.pow(3)), "1.00GiB");
+        assert_eq!(format_bytes(KIB.pow(4)), "1.00TiB");
+        assert_eq!(format_bytes(KIB.pow(5)), "1024.00TiB");
+    }
+
+    #[cfg(not(feature = "alloc-stats"))]
+    #[test]
+    fn measure_without_tracking() {
+        assert_eq!(measure(|| vec![0u8; 1024].len()), (1024, None));
+    }
+
+    #[cfg(feature = "alloc-stats")]
+    #[test]
+    fn measure_counts_allocations() {
+        const SIZE: usize = 8 << 20;
+        let (len, stats) = measure(|| {
+            let buffer = vec![1u8; SIZE];
+            std::hint::black_box(&buffer).len()
+        });
+        let stats = stats.unwrap();
+        assert_eq!(len, SIZE);
+
+        // other tests allocate on other threads into the same counters, so only lower bounds are
+        // exact; the slack covers anything they free while the buffer is live
+        assert!(stats.allocations >= 1, "{stats:?}");
+        assert!(stats.bytes_allocated >= SIZE as u64, "{stats:?}");
+        assert!(stats.peak_bytes >= SIZE as u64 - (1 << 20), "{stats:?}");
+
+        let (_, idle) = measure(|| ());
+        assert!(idle.unwrap().peak_bytes < SIZE as u64, "{idle:?}");
+    }
+}
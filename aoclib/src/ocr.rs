@@ -0,0 +1,195 @@
+use std::fmt;
+
+use crate::grid::Grid;
+
+/// The block-letter fonts puzzles draw their answers in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Font {
+    /// Letters 4 pixels wide and 6 tall, as on the 2016 and 2022 displays.
+    Small,
+    /// Letters 6 pixels wide and 10 tall, as formed by the 2018 moving points.
+    Large,
+}
+
+impl Font {
+    pub fn height(self) -> usize {
+        match self {
+            Self::Small => 6,
+            Self::Large => 10,
+        }
+    }
+
+    fn from_height(height: usize) -> Option<Self> {
+        [Self::Small, Self::Large].into_iter().find(|font| font.height() == height)
+    }
+
+    fn glyphs(self) -> &'static [(char, &'static str)] {
+        match self {
+            Self::Small => SMALL_GLYPHS,
+            Self::Large => LARGE_GLYPHS,
+        }
+    }
+}
+
+const SMALL_GLYPHS: &[(char, &str)] = &[
+    ('A', ".##.\n#..#\n#..#\n####\n#..#\n#..#"),
+    ('B', "###.\n#..#\n###.\n#..#\n#..#\n###."),
+    ('C', ".##.\n#..#\n#...\n#...\n#..#\n.##."),
+    ('E', "####\n#...\n###.\n#...\n#...\n####"),
+    ('F', "####\n#...\n###.\n#...\n#...\n#..."),
+    ('G', ".##.\n#..#\n#...\n#.##\n#..#\n.###"),
+    ('H', "#..#\n#..#\n####\n#..#\n#..#\n#..#"),
+    ('I', "###\n.#.\n.#.\n.#.\n.#.\n###"),
+    ('J', "..##\n...#\n...#\n...#\n#..#\n.##."),
+    ('K', "#..#\n#.#.\n##..\n#.#.\n#.#.\n#..#"),
+    ('L', "#...\n#...\n#...\n#...\n#...\n####"),
+    ('O', ".##.\n#..#\n#..#\n#..#\n#..#\n.##."),
+    ('P', "###.\n#..#\n#..#\n###.\n#...\n#..."),
+    ('R', "###.\n#..#\n#..#\n###.\n#.#.\n#..#"),
+    ('S', ".###\n#...\n#...\n.##.\n...#\n###."),
+    ('U', "#..#\n#..#\n#..#\n#..#\n#..#\n.##."),
+    ('Y', "#...#\n#...#\n.#.#.\n..#..\n..#..\n..#.."),
+    ('Z', "####\n...#\n..#.\n.#..\n#...\n####"),
+];
+
+const LARGE_GLYPHS: &[(char, &str)] = &[
+    ('A', "..##..\n.#..#.\n#....#\n#....#\n#....#\n######\n#....#\n#....#\n#....#\n#....#"),
+    ('B', "#####.\n#....#\n#....#\n#....#\n#####.\n#....#\n#....#\n#....#\n#....#\n#####."),
+    ('C', ".####.\n#....#\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#....#\n.####."),
+    ('E', "######\n#.....\n#.....\n#.....\n#####.\n#.....\n#.....\n#.....\n#.....\n######"),
+    ('F', "######\n#.....\n#.....\n#.....\n#####.\n#.....\n#.....\n#.....\n#.....\n#....."),
+    ('G', ".####.\n#....#\n#.....\n#.....\n#.....\n#..###\n#....#\n#....#\n#...##\n.###.#"),
+    ('H', "#....#\n#....#\n#....#\n#....#\n######\n#....#\n#....#\n#....#\n#....#\n#....#"),
+    ('J', "...###\n....#.\n....#.\n....#.\n....#.\n....#.\n#...#.\n#...#.\n#...#.\n.###.."),
+    ('K', "#....#\n#...#.\n#..#..\n#.#...\n##....\n##....\n#.#...\n#..#..\n#...#.\n#....#"),
+    ('L', "#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n######"),
+    ('N', "#....#\n##...#\n##...#\n#.#..#\n#.#..#\n#..#.#\n#..#.#\n#...##\n#...##\n#....#"),
+    ('P', "#####.\n#....#\n#....#\n#....#\n#####.\n#.....\n#.....\n#.....\n#.....\n#....."),
+    ('R', "#####.\n#....#\n#....#\n#....#\n#####.\n#..#..\n#...#.\n#...#.\n#....#\n#....#"),
+    ('X', "#....#\n#....#\n.#..#.\n.#..#.\n..##..\n..##..\n.#..#.\n.#..#.\n#....#\n#....#"),
+    ('Z', "######\n.....#\n.....#\n....#.\n...#..\n..#...\n.#....\n#.....\n#.....\n######"),
+];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum OcrError {
+    /// No lit pixels at all.
+    Empty,
+    /// The lit rows don't match the height of any known font.
+    UnsupportedHeight(usize),
+    /// A letter-shaped block didn't match any glyph of the font.
+    UnknownGlyph { index: usize, column: usize, glyph: String },
+}
+
+impl fmt::Display for OcrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "image has no lit pixels"),
+            Self::UnsupportedHeight(height) => write!(f, "no font is {height} pixels tall"),
+            Self::UnknownGlyph { index, column, glyph } => {
+                write!(f, "letter {} (at column {}) is not recognized:\n{}", index + 1, column, glyph)
+            }
+        }
+    }
+}
+
+impl std::error::Error for OcrError {}
+
+/// Reads the letters drawn in `image`, where `#` and `█` are lit pixels and anything else is dark.
+pub fn recognize(image: &str) -> Result<String, OcrError> {
+    let rows: Vec<Vec<bool>> = image.lines().map(|line| line.chars().map(|c| c == '#' || c == '█').collect()).collect();
+    recognize_rows(rows)
+}
+
+/// Reads the letters drawn in `grid`, with `lit` deciding which cells are lit pixels.
+pub fn recognize_grid<T>(grid: &Grid<T>, lit: impl Fn(&T) -> bool) -> Result<String, OcrError> {
+    recognize_rows(grid.rows().map(|row| row.iter().map(&lit).collect()).collect())
+}
+
+fn recognize_rows(mut rows: Vec<Vec<bool>>) -> Result<String, OcrError> {
+    // blank rows around the letters, like the margin of a bounding box, are ignored
+    let Some(top) = rows.iter().position(|row| row.contains(&true)) else {
+        return Err(OcrError::Empty);
+    };
+    let bottom = rows.iter().rposition(|row| row.contains(&true)).unwrap();
+    rows.truncate(bottom + 1);
+    rows.drain(..top);
+
+    let font = Font::from_height(rows.len()).ok_or(OcrError::UnsupportedHeight(rows.len()))?;
+    let width = rows.iter().map(Vec::len).max().unwrap();
+    let lit = |x: usize, y: usize| rows[y].get(x).copied().unwrap_or(false);
+    let column_lit = |x: usize| (0..rows.len()).any(|y| lit(x, y));
+
+    // letters are separated by at least one dark column and never contain one
+    let mut text = String::new();
+    let mut x = 0;
+    while x < width {
+        if !column_lit(x) {
+            x += 1;
+            continue;
+        }
+
+        let start = x;
+        while x < width && column_lit(x) {
+            x += 1;
+        }
+
+        let glyph = (0..rows.len())
+            .map(|y| (start..x).map(|x| if lit(x, y) { '#' } else { '.' }).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        match font.glyphs().iter().find(|(_, pattern)| *pattern == glyph) {
+            Some(&(letter, _)) => text.push(letter),
+            None => return Err(OcrError::UnknownGlyph { index: text.len(), column: start, glyph }),
+        }
+    }
+
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Draws `glyphs` side by side with a dark column between each.
+    fn draw(glyphs: &[&str]) -> String {
+        let rows: Vec<Vec<&str>> = glyphs.iter().map(|glyph| glyph.lines().collect()).collect();
+        (0..rows[0].len()).map(|y| rows.iter().map(|glyph| glyph[y]).collect::<Vec<_>>().join(".")).collect::<Vec<_>>().join("\n")
+    }
+
+    #[test]
+    fn every_glyph_is_recognized() {
+        for font in [Font::Small, Font::Large] {
+            let glyphs: Vec<&str> = font.glyphs().iter().map(|&(_, glyph)| glyph).collect();
+            let expected: String = font.glyphs().iter().map(|&(letter, _)| letter).collect();
+            assert_eq!(recognize(&draw(&glyphs)), Ok(expected), "{font:?} font");
+            assert!(glyphs.iter().all(|glyph| glyph.lines().count() == font.height()));
+        }
+    }
+
+    #[test]
+    fn margins_and_block_pixels_are_accepted() {
+        let image = "\n......\n.██...\n█..█..\n█..█..\n████..\n█..█..\n█..█..\n......\n";
+        assert_eq!(recognize(image), Ok("A".to_string()));
+    }
+
+    #[test]
+    fn recognizes_a_grid() {
+        let text = draw(&[SMALL_GLYPHS[6].1, SMALL_GLYPHS[1].1]);
+        let grid = Grid::parse(&text, |c| Some(c == '#')).unwrap();
+        assert_eq!(recognize_grid(&grid, |&lit| lit), Ok("HB".to_string()));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(recognize("....\n...."), Err(OcrError::Empty));
+        assert_eq!(recognize("#\n#\n#"), Err(OcrError::UnsupportedHeight(3)));
+
+        let unknown = "####.####\n#....#..#\n#....#..#\n#....#..#\n#....#..#\n####.#..#";
+        let error = recognize(unknown).unwrap_err();
+        assert!(matches!(error, OcrError::UnknownGlyph { index: 0, column: 0, .. }), "{error:?}");
+
+        let after_a_letter = draw(&[SMALL_GLYPHS[0].1, "#\n#\n#\n#\n#\n#"]);
+        assert!(matches!(recognize(&after_a_letter), Err(OcrError::UnknownGlyph { index: 1, column: 5, .. })));
+    }
+}
@@ -0,0 +1,369 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// A number found by [`ints`] or [`uints`] that doesn't fit the requested type.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NumberError {
+    /// The byte offset of the number in the input.
+    pub offset: usize,
+    pub token: String,
+    pub type_name: &'static str,
+    pub reason: String,
+}
+
+impl fmt::Display for NumberError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} at offset {} is not a valid {}: {}", self.token, self.offset, self.type_name, self.reason)
+    }
+}
+
+impl std::error::Error for NumberError {}
+
+/// Every integer in `s`, in order. A `-` directly before a digit is a sign unless it follows
+/// another digit, so `x=-3` yields `-3` but `1-5` yields `1` and `5`. Fails on the first number
+/// that doesn't fit in `T`.
+pub fn ints<T: FromStr>(s: &str) -> Result<Vec<T>, NumberError>
+where
+    T::Err: fmt::Display,
+{
+    parse_spans(s, true)
+}
+
+/// Every run of digits in `s`, ignoring any signs. Fails on the first number that doesn't fit in `T`.
+pub fn uints<T: FromStr>(s: &str) -> Result<Vec<T>, NumberError>
+where
+    T::Err: fmt::Display,
+{
+    parse_spans(s, false)
+}
+
+fn parse_spans<T: FromStr>(s: &str, signed: bool) -> Result<Vec<T>, NumberError>
+where
+    T::Err: fmt::Display,
+{
+    number_spans(s, signed).map(|(offset, span)| {
+        span.parse().map_err(|e: T::Err| NumberError {
+            offset,
+            token: span.to_string(),
+            type_name: std::any::type_name::<T>(),
+            reason: e.to_string(),
+        })
+    }).collect()
+}
+
+fn number_spans(s: &str, signed: bool) -> impl Iterator<Item = (usize, &str)> {
+    let bytes = s.as_bytes();
+    let mut i = 0;
+
+    std::iter::from_fn(move || {
+        while i < bytes.len() {
+            let negative = signed && bytes[i] == b'-'
+                && bytes.get(i + 1).is_some_and(u8::is_ascii_digit)
+                && (i == 0 || !bytes[i - 1].is_ascii_digit());
+
+            if negative || bytes[i].is_ascii_digit() {
+                let start = i;
+                i += 1;
+                while i < bytes.len() && bytes[i].is_ascii_digit() {
+                    i += 1;
+                }
+                return Some((start, &s[start..i]));
+            }
+
+            i += 1;
+        }
+
+        None
+    })
+}
+
+/// Splits input on blank lines, tolerating `\r\n` line endings and any number of blank lines
+/// between paragraphs. Empty paragraphs are skipped.
+pub fn paragraphs(input: &str) -> impl Iterator<Item = &str> {
+    let mut rest = input;
+
+    std::iter::from_fn(move || {
+        rest = rest.trim_start_matches(['\r', '\n']);
+        if rest.is_empty() {
+            return None;
+        }
+
+        let mut offset = 0;
+        for line in rest.split_inclusive('\n') {
+            if line.trim_end_matches(['\r', '\n']).is_empty() {
+                break;
+            }
+            offset += line.len();
+        }
+
+        let (paragraph, tail) = rest.split_at(offset);
+        rest = tail;
+        Some(paragraph.trim_end_matches(['\r', '\n']))
+    })
+}
+
+/// Cuts a line into cells of `width` characters separated by `gap` characters, as in a
+/// column diagram like `[A] [B]     [D]`. A short final cell is kept; missing cells past the
+/// end of a ragged line are not returned.
+pub fn fixed_width(line: &str, width: usize, gap: usize) -> Vec<&str> {
+    assert!(width > 0, "cell width must be positive");
+
+    let chars: Vec<(usize, char)> = line.char_indices().collect();
+    let mut cells = vec![];
+    let mut i = 0;
+    while i < chars.len() {
+        let start = chars[i].0;
+        let end = chars.get(i + width).map_or(line.len(), |&(offset, _)| offset);
+        cells.push(&line[start..end]);
+        i += width + gap;
+    }
+
+    cells
+}
+
+/// The byte offset and text of each whitespace-separated token, for lining up cells
+/// against a header or label row.
+pub fn token_offsets(line: &str) -> Vec<(usize, &str)> {
+    let mut tokens = vec![];
+    let mut start = None;
+    for (i, c) in line.char_indices().chain([(line.len(), ' ')]) {
+        match (start, c.is_whitespace()) {
+            (None, false) => start = Some(i),
+            (Some(s), true) => {
+                tokens.push((s, &line[s..i]));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+
+    tokens
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ScanErrorKind {
+    /// The input didn't contain the pattern's literal text where expected. `column` counts
+    /// characters from 0.
+    Mismatch { expected: String, column: usize },
+    /// The pattern had a different number of `{}` placeholders than values requested.
+    PlaceholderCount { expected: usize, found: usize },
+    /// A captured value couldn't be parsed as the requested type.
+    InvalidValue { index: usize, value: String, type_name: &'static str, reason: String },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScanError {
+    pub input: String,
+    pub pattern: &'static str,
+    pub kind: ScanErrorKind,
+}
+
+impl fmt::Display for ScanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            ScanErrorKind::Mismatch { expected, column } => {
+                write!(f, "expected {:?} at column {} of {:?} (pattern {:?})", expected, column + 1, self.input, self.pattern)
+            }
+            ScanErrorKind::PlaceholderCount { expected, found } => {
+                write!(f, "pattern {:?} has {} placeholders, but {} values were requested", self.pattern, found, expected)
+            }
+            ScanErrorKind::InvalidValue { index, value, type_name, reason } => {
+                write!(f, "value {} ({:?}) of {:?} is not a valid {}: {}", index + 1, value, self.input, type_name, reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ScanError {}
+
+/// Matches `input` against `pattern`, where each `{}` captures text up to the next literal
+/// part of the pattern. The last literal must end the input, so a final `{}` takes the rest
+/// and a trailing literal is matched at the very end. Returns the captured text.
+pub fn scan_str<'a>(input: &'a str, pattern: &'static str) -> Result<Vec<&'a str>, ScanError> {
+    let error = |kind| ScanError { input: input.to_string(), pattern, kind };
+    let column_of = |rest: &str| input[..input.len() - rest.len()].chars().count();
+
+    let literals: Vec<&str> = pattern.split("{}").collect();
+    let first = literals[0];
+    let mut rest = input.strip_prefix(first).ok_or_else(|| error(ScanErrorKind::Mismatch { expected: first.to_string(), column: 0 }))?;
+
+    let mut captures = vec![];
+    for (i, &literal) in literals.iter().enumerate().skip(1) {
+        let column = column_of(rest);
+        let found = if i == literals.len() - 1 {
+            rest.strip_suffix(literal).map(str::len)
+        } else {
+            rest.find(literal)
+        };
+        let end = found.ok_or_else(|| error(ScanErrorKind::Mismatch { expected: literal.to_string(), column }))?;
+
+        captures.push(&rest[..end]);
+        rest = &rest[end + literal.len()..];
+    }
+
+    if !rest.is_empty() {
+        let column = column_of(rest);
+        return Err(error(ScanErrorKind::Mismatch { expected: "end of input".to_string(), column }));
+    }
+
+    Ok(captures)
+}
+
+/// Parses the captures of a [`scan_str`] match into typed values; implemented for tuples.
+pub trait FromCaptures: Sized {
+    const COUNT: usize;
+
+    fn from_captures(captures: &[&str]) -> Result<Self, (usize, &'static str, String)>;
+}
+
+fn parse_capture<T: FromStr>(captures: &[&str], index: usize) -> Result<T, (usize, &'static str, String)>
+where
+    T::Err: fmt::Display,
+{
+    captures[index].trim().parse().map_err(|e: T::Err| (index, std::any::type_name::<T>(), e.to_string()))
+}
+
+macro_rules! impl_from_captures {
+    ($count:expr => $($t:ident $i:tt),+) => {
+        impl<$($t: FromStr),+> FromCaptures for ($($t,)+)
+        where
+            $($t::Err: fmt::Display),+
+        {
+            const COUNT: usize = $count;
+
+            fn from_captures(captures: &[&str]) -> Result<Self, (usize, &'static str, String)> {
+                Ok(($(parse_capture::<$t>(captures, $i)?,)+))
+            }
+        }
+    };
+}
+
+impl_from_captures!(1 => A 0);
+impl_from_captures!(2 => A 0, B 1);
+impl_from_captures!(3 => A 0, B 1, C 2);
+impl_from_captures!(4 => A 0, B 1, C 2, D 3);
+impl_from_captures!(5 => A 0, B 1, C 2, D 3, E 4);
+impl_from_captures!(6 => A 0, B 1, C 2, D 3, E 4, F 5);
+impl_from_captures!(7 => A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+impl_from_captures!(8 => A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+
+/// Matches `input` against `pattern` and parses each `{}` into the corresponding tuple field.
+/// Surrounding whitespace in captured values is ignored. See also [`scan!`](crate::scan).
+pub fn scan<T: FromCaptures>(input: &str, pattern: &'static str) -> Result<T, ScanError> {
+    let captures = scan_str(input, pattern)?;
+    let error = |kind| ScanError { input: input.to_string(), pattern, kind };
+
+    if captures.len() != T::COUNT {
+        return Err(error(ScanErrorKind::PlaceholderCount { expected: T::COUNT, found: captures.len() }));
+    }
+
+    T::from_captures(&captures).map_err(|(index, type_name, reason)| {
+        error(ScanErrorKind::InvalidValue { index, value: captures[index].to_string(), type_name, reason })
+    })
+}
+
+/// Parses a line against a `{}` pattern into a tuple.
+///
+/// `scan!(line, "move {} from {} to {}", u32, usize, usize)` names the types explicitly;
+/// `scan!(line, "move {} from {} to {}")` infers them from how the result is used.
+#[macro_export]
+macro_rules! scan {
+    ($input:expr, $pattern:literal) => {
+        $crate::parse::scan($input, $pattern)
+    };
+    ($input:expr, $pattern:literal, $($t:ty),+ $(,)?) => {
+        $crate::parse::scan::<($($t,)+)>($input, $pattern)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ints_and_signs() {
+        assert_eq!(ints::<i32>("x=-3, y=14"), Ok(vec![-3, 14]));
+        assert_eq!(ints::<i32>("1-5 -2"), Ok(vec![1, 5, -2]));
+        assert_eq!(ints::<i64>("no numbers here"), Ok(vec![]));
+        assert_eq!(uints::<u32>("x=-3, y=14"), Ok(vec![3, 14]));
+    }
+
+    #[test]
+    fn ints_report_numbers_that_do_not_fit() {
+        let error = ints::<u8>("1, 2, 300, 4").unwrap_err();
+        assert_eq!((error.offset, error.token.as_str(), error.type_name), (6, "300", "u8"));
+        assert_eq!(ints::<u32>("a -7").unwrap_err().token, "-7");
+        assert!(uints::<i8>("128").unwrap_err().to_string().starts_with("\"128\" at offset 0 is not a valid i8"));
+    }
+
+    #[test]
+    fn paragraphs_split_on_blank_lines() {
+        let input = "\na\nb\n\n\nc\r\n\r\nd\r\n";
+        assert_eq!(paragraphs(input).collect::<Vec<_>>(), vec!["a\nb", "c", "d"]);
+        assert_eq!(paragraphs("\n\n").count(), 0);
+    }
+
+    #[test]
+    fn fixed_width_cells() {
+        assert_eq!(fixed_width("[A] [B]     [D]", 3, 1), vec!["[A]", "[B]", "   ", "[D]"]);
+        assert_eq!(fixed_width("[A]  ", 3, 1), vec!["[A]", " "]);
+        assert_eq!(fixed_width("", 3, 1), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn token_offsets_of_labels() {
+        assert_eq!(token_offsets(" 1   2  10 "), vec![(1, "1"), (5, "2"), (8, "10")]);
+        assert_eq!(token_offsets("   "), vec![]);
+    }
+
+    #[test]
+    fn scan_str_captures() {
+        assert_eq!(scan_str("move 3 from 1 to 2", "move {} from {} to {}"), Ok(vec!["3", "1", "2"]));
+        assert_eq!(scan_str("abc", "{}"), Ok(vec!["abc"]));
+        assert_eq!(scan_str("exact", "exact"), Ok(vec![]));
+    }
+
+    #[test]
+    fn scan_str_anchors_the_last_literal_to_the_end() {
+        assert_eq!(scan_str("x is 1.5.", "{} is {}."), Ok(vec!["x", "1.5"]));
+        assert_eq!(scan_str("(a) (b)", "({})"), Ok(vec!["a) (b"]));
+    }
+
+    #[test]
+    fn scan_str_mismatches() {
+        let kind = |input, pattern| scan_str(input, pattern).unwrap_err().kind;
+        assert_eq!(kind("mov 3", "move {}"), ScanErrorKind::Mismatch { expected: "move ".to_string(), column: 0 });
+        assert_eq!(kind("move 3 to 4", "move {} from {}"), ScanErrorKind::Mismatch { expected: " from ".to_string(), column: 5 });
+        assert_eq!(kind("x is 1!", "{} is {}."), ScanErrorKind::Mismatch { expected: ".".to_string(), column: 5 });
+        assert_eq!(kind("exact!", "exact"), ScanErrorKind::Mismatch { expected: "end of input".to_string(), column: 5 });
+
+        // columns count characters, not bytes
+        assert_eq!(kind("naïve 3 → 4", "naïve {} -> {}"), ScanErrorKind::Mismatch { expected: " -> ".to_string(), column: 6 });
+        assert_eq!(kind("ünïcödé!", "ünïcödé"), ScanErrorKind::Mismatch { expected: "end of input".to_string(), column: 7 });
+        let err = scan_str("größe: 3 m²", "größe: {} m").unwrap_err();
+        assert_eq!(err.kind, ScanErrorKind::Mismatch { expected: " m".to_string(), column: 7 });
+        assert!(err.to_string().contains("at column 8 "), "{err}");
+    }
+
+    #[test]
+    fn scan_typed_values() {
+        let (count, from, to): (usize, String, char) = scan("move 3 from here to x", "move {} from {} to {}").unwrap();
+        assert_eq!((count, from.as_str(), to), (3, "here", 'x'));
+        assert_eq!(crate::scan!("p=< 4,-2>", "p=<{},{}>", i32, i32), Ok((4, -2)));
+    }
+
+    #[test]
+    fn scan_errors() {
+        let error = crate::scan!("a=1 b=x", "a={} b={}", u8, u8).unwrap_err();
+        assert_eq!(error.kind, ScanErrorKind::InvalidValue {
+            index: 1,
+            value: "x".to_string(),
+            type_name: "u8",
+            reason: "invalid digit found in string".to_string(),
+        });
+        assert_eq!(error.to_string(), "value 2 (\"x\") of \"a=1 b=x\" is not a valid u8: invalid digit found in string");
+
+        let error = crate::scan!("a=1 b=2", "a={} b={}", u8).unwrap_err();
+        assert_eq!(error.kind, ScanErrorKind::PlaceholderCount { expected: 1, found: 2 });
+    }
+}
@@ -0,0 +1,352 @@
+use std::fmt;
+use std::ops::RangeInclusive;
+
+/// Integer types a [`RangeSet`] can hold.
+pub trait Discrete: Copy + Ord + fmt::Debug {
+    const MIN: Self;
+    const MAX: Self;
+
+    /// `self + 1`, or `None` at the maximum value.
+    fn succ(self) -> Option<Self>;
+    /// `self - 1`, or `None` at the minimum value.
+    fn pred(self) -> Option<Self>;
+    /// The number of values in `start..=end`, which must not be empty.
+    fn count(start: Self, end: Self) -> u128;
+}
+
+macro_rules! impl_discrete {
+    ($($t:ty),*) => {$(
+        impl Discrete for $t {
+            const MIN: Self = <$t>::MIN;
+            const MAX: Self = <$t>::MAX;
+
+            fn succ(self) -> Option<Self> {
+                self.checked_add(1)
+            }
+
+            fn pred(self) -> Option<Self> {
+                self.checked_sub(1)
+            }
+
+            fn count(start: Self, end: Self) -> u128 {
+                (end as i128).abs_diff(start as i128) + 1
+            }
+        }
+    )*};
+}
+
+impl_discrete!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+/// A set of integers stored as sorted, disjoint inclusive ranges. Overlapping and adjacent
+/// ranges are merged on insertion, so `1..=3` and `4..=6` become `1..=6`.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct RangeSet<T> {
+    ranges: Vec<(T, T)>,
+}
+
+impl<T: Discrete> Default for RangeSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Discrete> RangeSet<T> {
+    pub fn new() -> Self {
+        Self { ranges: vec![] }
+    }
+
+    /// The number of disjoint ranges, not the number of values; see [`RangeSet::total_len`].
+    pub fn len(&self) -> usize {
+        self.ranges.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.ranges.clear();
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = RangeInclusive<T>> + ExactSizeIterator + '_ {
+        self.ranges.iter().map(|&(start, end)| start..=end)
+    }
+
+    pub fn min(&self) -> Option<T> {
+        self.ranges.first().map(|&(start, _)| start)
+    }
+
+    pub fn max(&self) -> Option<T> {
+        self.ranges.last().map(|&(_, end)| end)
+    }
+
+    /// The number of values covered.
+    pub fn total_len(&self) -> u128 {
+        self.ranges.iter().map(|&(start, end)| T::count(start, end)).sum()
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let i = self.ranges.partition_point(|&(_, end)| end < value);
+        self.ranges.get(i).is_some_and(|&(start, _)| start <= value)
+    }
+
+    /// Whether every value of `range` is in the set.
+    pub fn contains_range(&self, range: RangeInclusive<T>) -> bool {
+        let (start, end) = range.into_inner();
+        if start > end {
+            return true;
+        }
+
+        let i = self.ranges.partition_point(|&(_, e)| e < start);
+        self.ranges.get(i).is_some_and(|&(s, e)| s <= start && end <= e)
+    }
+
+    /// Adds a range, merging it with any ranges it overlaps or touches. Empty ranges are ignored.
+    pub fn insert(&mut self, range: RangeInclusive<T>) {
+        let (mut start, mut end) = range.into_inner();
+        if start > end {
+            return;
+        }
+
+        // the first range that ends at or after start - 1, and the first that starts after end + 1
+        let first = self.ranges.partition_point(|&(_, e)| e.succ().is_some_and(|after| after < start));
+        let last = self.ranges.partition_point(|&(s, _)| s.pred().is_none_or(|before| before <= end));
+
+        if first < last {
+            start = start.min(self.ranges[first].0);
+            end = end.max(self.ranges[last - 1].1);
+        }
+
+        self.ranges.splice(first..last, [(start, end)]);
+    }
+
+    pub fn insert_value(&mut self, value: T) {
+        self.insert(value..=value);
+    }
+
+    /// Removes every value of `range` from the set, splitting ranges where needed.
+    pub fn remove(&mut self, range: RangeInclusive<T>) {
+        let (start, end) = range.into_inner();
+        if start > end {
+            return;
+        }
+
+        let first = self.ranges.partition_point(|&(_, e)| e < start);
+        let last = self.ranges.partition_point(|&(s, _)| s <= end);
+        if first >= last {
+            return;
+        }
+
+        let mut kept = vec![];
+        let (head_start, _) = self.ranges[first];
+        let (_, tail_end) = self.ranges[last - 1];
+        if head_start < start {
+            kept.push((head_start, start.pred().unwrap()));
+        }
+        if end < tail_end {
+            kept.push((end.succ().unwrap(), tail_end));
+        }
+
+        self.ranges.splice(first..last, kept);
+    }
+
+    pub fn remove_value(&mut self, value: T) {
+        self.remove(value..=value);
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut result = self.clone();
+        for range in other.iter() {
+            result.insert(range);
+        }
+        result
+    }
+
+    /// Values in `self` but not in `other`.
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut result = self.clone();
+        for range in other.iter() {
+            result.remove(range);
+        }
+        result
+    }
+
+    /// Values in both `self` and `other`.
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = vec![];
+        let (mut i, mut j) = (0, 0);
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (a_start, a_end) = self.ranges[i];
+            let (b_start, b_end) = other.ranges[j];
+
+            let (start, end) = (a_start.max(b_start), a_end.min(b_end));
+            if start <= end {
+                ranges.push((start, end));
+            }
+
+            if a_end < b_end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+
+        Self { ranges }
+    }
+
+    /// The set restricted to `bounds`.
+    pub fn clamp(&self, bounds: RangeInclusive<T>) -> Self {
+        self.intersection(&RangeSet::from_iter([bounds]))
+    }
+
+    /// The ranges within `bounds` that are not in the set, in ascending order.
+    pub fn gaps(&self, bounds: RangeInclusive<T>) -> impl Iterator<Item = RangeInclusive<T>> + '_ {
+        let (lower, upper) = bounds.into_inner();
+        let mut cursor = (lower <= upper).then_some(lower);
+        let mut ranges = self.ranges.iter().skip_while(move |&&(_, end)| end < lower);
+
+        std::iter::from_fn(move || loop {
+            let from = cursor?;
+            match ranges.next() {
+                Some(&(start, end)) if start <= upper => {
+                    cursor = end.succ().filter(|&next| next <= upper);
+                    if from < start {
+                        return Some(from..=start.pred().unwrap());
+                    }
+                }
+                _ => {
+                    cursor = None;
+                    return Some(from..=upper);
+                }
+            }
+        })
+    }
+}
+
+impl<T: Discrete> FromIterator<RangeInclusive<T>> for RangeSet<T> {
+    fn from_iter<I: IntoIterator<Item = RangeInclusive<T>>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<T: Discrete> Extend<RangeInclusive<T>> for RangeSet<T> {
+    fn extend<I: IntoIterator<Item = RangeInclusive<T>>>(&mut self, iter: I) {
+        for range in iter {
+            self.insert(range);
+        }
+    }
+}
+
+impl<T: Discrete> fmt::Debug for RangeSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ranges<T: Discrete>(set: &RangeSet<T>) -> Vec<RangeInclusive<T>> {
+        set.iter().collect()
+    }
+
+    #[test]
+    #[allow(clippy::reversed_empty_ranges)]
+    fn insert_merges_overlapping_and_adjacent() {
+        let mut set = RangeSet::new();
+        set.insert(1..=3);
+        set.insert(4..=6);
+        set.insert(10..=12);
+        assert_eq!(ranges(&set), vec![1..=6, 10..=12]);
+
+        set.insert(5..=9);
+        assert_eq!(ranges(&set), vec![1..=12]);
+
+        set.insert(20..=19);
+        set.insert_value(14);
+        assert_eq!(ranges(&set), vec![1..=12, 14..=14]);
+        assert_eq!((set.len(), set.total_len()), (2, 13));
+        assert_eq!((set.min(), set.max()), (Some(1), Some(14)));
+    }
+
+    #[test]
+    fn insert_swallows_several_ranges() {
+        let mut set: RangeSet<i32> = [0..=1, 3..=4, 6..=7, 20..=21].into_iter().collect();
+        set.insert(2..=10);
+        assert_eq!(ranges(&set), vec![0..=10, 20..=21]);
+    }
+
+    #[test]
+    #[allow(clippy::reversed_empty_ranges)]
+    fn contains() {
+        let set: RangeSet<i64> = [-5..=-1, 3..=8].into_iter().collect();
+        assert!(set.contains(-5) && set.contains(-1) && set.contains(8));
+        assert!(!set.contains(0) && !set.contains(9) && !set.contains(-6));
+        assert!(set.contains_range(4..=7));
+        assert!(!set.contains_range(-2..=4));
+        assert!(set.contains_range(10..=9));
+    }
+
+    #[test]
+    fn remove_splits_ranges() {
+        let mut set: RangeSet<u32> = [0..=10, 20..=30].into_iter().collect();
+        set.remove(5..=24);
+        assert_eq!(ranges(&set), vec![0..=4, 25..=30]);
+
+        set.remove_value(0);
+        set.remove(40..=50);
+        assert_eq!(ranges(&set), vec![1..=4, 25..=30]);
+
+        set.remove(0..=100);
+        assert!(set.is_empty());
+    }
+
+    #[test]
+    fn extreme_values() {
+        let mut set = RangeSet::new();
+        set.insert(i8::MIN..=-100);
+        set.insert(100..=i8::MAX);
+        set.insert(-99..=-99);
+        assert_eq!(ranges(&set), vec![i8::MIN..=-99, 100..=i8::MAX]);
+        assert_eq!(set.total_len(), 30 + 28);
+
+        set.insert(i8::MIN..=i8::MAX);
+        assert_eq!(set.total_len(), 256);
+        assert_eq!(set.gaps(i8::MIN..=i8::MAX).count(), 0);
+
+        set.remove(i8::MIN..=i8::MIN);
+        set.remove(i8::MAX..=i8::MAX);
+        assert_eq!(ranges(&set), vec![-127..=126]);
+    }
+
+    #[test]
+    fn set_operations() {
+        let a: RangeSet<i32> = [0..=10, 20..=30].into_iter().collect();
+        let b: RangeSet<i32> = [5..=25].into_iter().collect();
+        assert_eq!(ranges(&a.union(&b)), vec![0..=30]);
+        assert_eq!(ranges(&a.intersection(&b)), vec![5..=10, 20..=25]);
+        assert_eq!(ranges(&a.difference(&b)), vec![0..=4, 26..=30]);
+        assert_eq!(ranges(&a.clamp(8..=22)), vec![8..=10, 20..=22]);
+        assert!(a.intersection(&RangeSet::new()).is_empty());
+    }
+
+    #[test]
+    #[allow(clippy::reversed_empty_ranges)]
+    fn gaps() {
+        let set: RangeSet<i32> = [3..=5, 8..=9, 15..=20].into_iter().collect();
+        assert_eq!(set.gaps(0..=16).collect::<Vec<_>>(), vec![0..=2, 6..=7, 10..=14]);
+        assert_eq!(set.gaps(4..=9).collect::<Vec<_>>(), vec![6..=7]);
+        assert_eq!(set.gaps(21..=25).collect::<Vec<_>>(), vec![21..=25]);
+        assert_eq!(set.gaps(5..=4).count(), 0);
+        assert_eq!(RangeSet::<u8>::new().gaps(0..=255).collect::<Vec<_>>(), vec![0..=255]);
+    }
+
+    #[test]
+    fn debug_format() {
+        let set: RangeSet<i32> = [1..=2, 5..=5].into_iter().collect();
+        assert_eq!(format!("{set:?}"), "{1..=2, 5..=5}");
+    }
+}
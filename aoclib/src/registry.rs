@@ -0,0 +1,190 @@
+use std::sync::OnceLock;
+
+use crate::{AocEntry, EntryKey};
+
+/// All submitted entries, sorted by year, day, part and variant (the default variant first).
+pub struct Registry {
+    entries: Vec<&'static AocEntry>,
+}
+
+pub fn registry() -> &'static Registry {
+    static REGISTRY: OnceLock<Registry> = OnceLock::new();
+    REGISTRY.get_or_init(|| Registry::from_entries(inventory::iter::<AocEntry>.into_iter().collect()))
+}
+
+impl Registry {
+    pub fn from_entries(mut entries: Vec<&'static AocEntry>) -> Self {
+        entries.sort_by_key(|entry| entry.key);
+        Self { entries }
+    }
+
+    pub fn entries(&self) -> &[&'static AocEntry] {
+        &self.entries
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn get(&self, key: &EntryKey) -> Option<&'static AocEntry> {
+        self.entries.binary_search_by_key(key, |entry| entry.key).ok().map(|i| self.entries[i])
+    }
+
+    pub fn years(&self) -> Vec<u32> {
+        let mut years: Vec<u32> = self.entries.iter().map(|entry| entry.year()).collect();
+        years.dedup();
+        years
+    }
+
+    pub fn latest_year(&self) -> Option<u32> {
+        self.entries.last().map(|entry| entry.year())
+    }
+
+    pub fn days(&self, year: u32) -> Vec<u32> {
+        let mut days: Vec<u32> = self.in_year(year).iter().map(|entry| entry.day()).collect();
+        days.dedup();
+        days
+    }
+
+    pub fn parts(&self, year: u32, day: u32) -> Vec<u32> {
+        let mut parts: Vec<u32> = self.in_day(year, day).iter().map(|entry| entry.part()).collect();
+        parts.dedup();
+        parts
+    }
+
+    pub fn in_year(&self, year: u32) -> &[&'static AocEntry] {
+        self.slice_where(|entry| entry.year().cmp(&year))
+    }
+
+    pub fn in_day(&self, year: u32, day: u32) -> &[&'static AocEntry] {
+        self.slice_where(|entry| (entry.year(), entry.day()).cmp(&(year, day)))
+    }
+
+    /// Every variant of a single puzzle part, starting with the default one if present.
+    pub fn variants(&self, year: u32, day: u32, part: u32) -> &[&'static AocEntry] {
+        self.slice_where(|entry| (entry.year(), entry.day(), entry.part()).cmp(&(year, day, part)))
+    }
+
+    /// The puzzle title, taken from whichever entry of the day declares one.
+    pub fn title(&self, year: u32, day: u32) -> Option<&'static str> {
+        self.in_day(year, day).iter().find_map(|entry| entry.meta.title)
+    }
+
+    /// Finds the preferred entry for a day and part. Without a year, the latest year is used.
+    pub fn find(&self, year: Option<u32>, day: u32, part: u32) -> Option<&'static AocEntry> {
+        let year = year.or_else(|| self.latest_year())?;
+        self.variants(year, day, part).first().copied()
+    }
+
+    pub fn print_list(&self) {
+        if self.is_empty() {
+            println!("No entries registered");
+            return;
+        }
+
+        for year in self.years() {
+            println!("{year}");
+            for day in self.days(year) {
+                if let Some(title) = self.title(year, day) {
+                    println!("  day {day:>2}: {title}");
+                } else {
+                    println!("  day {day:>2}");
+                }
+
+                for entry in self.in_day(year, day) {
+                    let variant = entry.variant().map_or(String::new(), |v| format!(" [{v}]"));
+                    println!("    part {}{}  {}  ({})", entry.part(), variant, entry.meta.name, entry.meta.location());
+                    if let Some(summary) = entry.meta.summary() {
+                        println!("      {summary}");
+                    }
+                }
+            }
+        }
+    }
+
+    fn slice_where(&self, cmp: impl Fn(&AocEntry) -> std::cmp::Ordering) -> &[&'static AocEntry] {
+        let start = self.entries.partition_point(|entry| cmp(entry).is_lt());
+        let end = self.entries.partition_point(|entry| cmp(entry).is_le());
+        &self.entries[start..end]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EntryMeta;
+
+    fn entry(year: u32, day: u32, part: u32, variant: Option<&'static str>) -> &'static AocEntry {
+        let meta = EntryMeta::new("solve", "tests");
+        Box::leak(Box::new(AocEntry::new(EntryKey::new(year, day, part, variant), meta, |input| input)))
+    }
+
+    fn keys(entries: &[&'static AocEntry]) -> Vec<(u32, u32, u32, Option<&'static str>)> {
+        entries.iter().map(|entry| (entry.year(), entry.day(), entry.part(), entry.variant())).collect()
+    }
+
+    fn sample() -> Registry {
+        Registry::from_entries(vec![
+            entry(2022, 2, 1, Some("fast")),
+            entry(2021, 25, 1, None),
+            entry(2022, 10, 1, None),
+            entry(2022, 2, 2, None),
+            entry(2022, 2, 1, None),
+            entry(2022, 2, 1, Some("brute")),
+            entry(2022, 1, 2, None),
+        ])
+    }
+
+    #[test]
+    fn entries_are_sorted_with_the_default_variant_first() {
+        assert_eq!(keys(sample().entries()), [
+            (2021, 25, 1, None),
+            (2022, 1, 2, None),
+            (2022, 2, 1, None),
+            (2022, 2, 1, Some("brute")),
+            (2022, 2, 1, Some("fast")),
+            (2022, 2, 2, None),
+            (2022, 10, 1, None),
+        ]);
+    }
+
+    #[test]
+    fn groups_by_year_day_and_part() {
+        let registry = sample();
+        assert_eq!(registry.years(), [2021, 2022]);
+        assert_eq!(registry.days(2022), [1, 2, 10]);
+        assert_eq!(registry.parts(2022, 2), [1, 2]);
+        assert_eq!(registry.in_year(2021).len(), 1);
+        assert_eq!(registry.in_day(2022, 2).len(), 4);
+        assert!(registry.in_day(2022, 3).is_empty());
+        assert!(registry.in_year(2020).is_empty());
+
+        assert_eq!(keys(registry.variants(2022, 2, 1)), [
+            (2022, 2, 1, None),
+            (2022, 2, 1, Some("brute")),
+            (2022, 2, 1, Some("fast")),
+        ]);
+        assert!(registry.variants(2022, 1, 1).is_empty());
+    }
+
+    #[test]
+    fn find_prefers_the_default_variant_and_latest_year() {
+        let registry = sample();
+        assert_eq!(registry.latest_year(), Some(2022));
+        assert_eq!(registry.find(None, 2, 1).map(AocEntry::variant), Some(None));
+        assert!(registry.find(None, 25, 1).is_none());
+        assert_eq!(registry.find(Some(2021), 25, 1).map(AocEntry::year), Some(2021));
+
+        // without a default, the alphabetically first variant is used
+        let registry = Registry::from_entries(vec![entry(2022, 3, 1, Some("z")), entry(2022, 3, 1, Some("a"))]);
+        assert_eq!(registry.find(None, 3, 1).map(AocEntry::variant), Some(Some("a")));
+
+        let empty = Registry::from_entries(vec![]);
+        assert_eq!(empty.latest_year(), None);
+        assert!(empty.find(None, 1, 1).is_none());
+    }
+}
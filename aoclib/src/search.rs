@@ -0,0 +1,335 @@
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+/// Edge weights usable by [`dijkstra`] and [`astar`].
+pub trait Cost: Copy + Ord + Add<Output = Self> {
+    const ZERO: Self;
+}
+
+macro_rules! impl_cost {
+    ($($t:ty),*) => {$(
+        impl Cost for $t {
+            const ZERO: Self = 0;
+        }
+    )*};
+}
+
+impl_cost!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Path<N, C> {
+    /// Every node from the start to the goal, inclusive.
+    pub nodes: Vec<N>,
+    pub cost: C,
+}
+
+impl<N, C> Path<N, C> {
+    pub fn start(&self) -> &N {
+        &self.nodes[0]
+    }
+
+    pub fn goal(&self) -> &N {
+        &self.nodes[self.nodes.len() - 1]
+    }
+
+    /// The number of edges walked.
+    pub fn steps(&self) -> usize {
+        self.nodes.len() - 1
+    }
+}
+
+/// Shortest distances from the start nodes to everything reachable, with the parent links
+/// needed to rebuild a path to any of them.
+#[derive(Clone, Debug)]
+pub struct DistanceMap<N, C> {
+    dist: HashMap<N, C>,
+    parents: HashMap<N, N>,
+}
+
+impl<N: Clone + Eq + Hash, C: Copy> DistanceMap<N, C> {
+    fn new() -> Self {
+        Self { dist: HashMap::new(), parents: HashMap::new() }
+    }
+
+    pub fn distance(&self, node: &N) -> Option<C> {
+        self.dist.get(node).copied()
+    }
+
+    pub fn contains(&self, node: &N) -> bool {
+        self.dist.contains_key(node)
+    }
+
+    pub fn len(&self) -> usize {
+        self.dist.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.dist.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&N, &C)> {
+        self.dist.iter()
+    }
+
+    pub fn into_map(self) -> HashMap<N, C> {
+        self.dist
+    }
+
+    /// The nodes from whichever start node is closest to `goal`, ending with `goal`.
+    pub fn path_to(&self, goal: &N) -> Option<Path<N, C>> {
+        let cost = self.distance(goal)?;
+        let mut nodes = vec![goal.clone()];
+        while let Some(parent) = self.parents.get(nodes.last().unwrap()) {
+            nodes.push(parent.clone());
+        }
+        nodes.reverse();
+
+        Some(Path { nodes, cost })
+    }
+}
+
+/// Breadth-first search from any of `starts`, stopping at the first node satisfying `is_goal`.
+pub fn bfs<N, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut successors: impl FnMut(&N) -> I,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Option<Path<N, usize>>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let (map, goal) = bfs_until(starts, &mut successors, &mut is_goal);
+    map.path_to(&goal?)
+}
+
+/// Breadth-first search from any of `starts` to every reachable node.
+pub fn bfs_all<N, I>(starts: impl IntoIterator<Item = N>, mut successors: impl FnMut(&N) -> I) -> DistanceMap<N, usize>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    bfs_until(starts, &mut successors, &mut |_| false).0
+}
+
+fn bfs_until<N, I>(
+    starts: impl IntoIterator<Item = N>,
+    successors: &mut impl FnMut(&N) -> I,
+    is_goal: &mut impl FnMut(&N) -> bool,
+) -> (DistanceMap<N, usize>, Option<N>)
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut map = DistanceMap::new();
+    let mut queue = VecDeque::new();
+
+    for start in starts {
+        if map.dist.insert(start.clone(), 0).is_none() {
+            queue.push_back(start);
+        }
+    }
+
+    while let Some(node) = queue.pop_front() {
+        if is_goal(&node) {
+            return (map, Some(node));
+        }
+
+        let depth = map.dist[&node];
+        for next in successors(&node) {
+            if let Entry::Vacant(e) = map.dist.entry(next.clone()) {
+                e.insert(depth + 1);
+                map.parents.insert(next.clone(), node.clone());
+                queue.push_back(next);
+            }
+        }
+    }
+
+    (map, None)
+}
+
+/// Dijkstra's algorithm from any of `starts`, stopping at the first settled node satisfying `is_goal`.
+pub fn dijkstra<N, C, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut successors: impl FnMut(&N) -> I,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Option<Path<N, C>>
+where
+    N: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let (map, goal) = best_first(starts, &mut successors, &mut |_| C::ZERO, &mut is_goal);
+    map.path_to(&goal?)
+}
+
+/// Dijkstra's algorithm from any of `starts` to every reachable node.
+pub fn dijkstra_all<N, C, I>(starts: impl IntoIterator<Item = N>, mut successors: impl FnMut(&N) -> I) -> DistanceMap<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (N, C)>,
+{
+    best_first(starts, &mut successors, &mut |_| C::ZERO, &mut |_| false).0
+}
+
+/// A* search. `heuristic` must never overestimate the remaining cost for the result to be optimal.
+pub fn astar<N, C, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut successors: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> C,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Option<Path<N, C>>
+where
+    N: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let (map, goal) = best_first(starts, &mut successors, &mut heuristic, &mut is_goal);
+    map.path_to(&goal?)
+}
+
+fn best_first<N, C, I>(
+    starts: impl IntoIterator<Item = N>,
+    successors: &mut impl FnMut(&N) -> I,
+    heuristic: &mut impl FnMut(&N) -> C,
+    is_goal: &mut impl FnMut(&N) -> bool,
+) -> (DistanceMap<N, C>, Option<N>)
+where
+    N: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut map = DistanceMap::new();
+    // nodes are kept out of the heap so they don't need to be Ord; the heap holds indices into `pushed`
+    let mut pushed: Vec<(N, C)> = vec![];
+    let mut heap = BinaryHeap::new();
+
+    for start in starts {
+        if map.dist.insert(start.clone(), C::ZERO).is_none() {
+            heap.push(Reverse((heuristic(&start), pushed.len())));
+            pushed.push((start, C::ZERO));
+        }
+    }
+
+    while let Some(Reverse((_, index))) = heap.pop() {
+        let (node, cost) = pushed[index].clone();
+        if map.dist[&node] < cost {
+            // a cheaper route to this node was settled after this entry was queued
+            continue;
+        }
+
+        if is_goal(&node) {
+            return (map, Some(node));
+        }
+
+        for (next, edge) in successors(&node) {
+            let next_cost = cost + edge;
+            let improved = match map.dist.get(&next) {
+                Some(&known) => next_cost < known,
+                None => true,
+            };
+
+            if improved {
+                map.dist.insert(next.clone(), next_cost);
+                map.parents.insert(next.clone(), node.clone());
+                heap.push(Reverse((next_cost + heuristic(&next), pushed.len())));
+                pushed.push((next, next_cost));
+            }
+        }
+    }
+
+    (map, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 5x5 open area with a wall down column 2, except at the bottom row.
+    fn open(x: i32, y: i32) -> bool {
+        (0..5).contains(&x) && (0..5).contains(&y) && !(x == 2 && y < 4)
+    }
+
+    fn moves(&(x, y): &(i32, i32)) -> Vec<(i32, i32)> {
+        [(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)].into_iter().filter(|&(x, y)| open(x, y)).collect()
+    }
+
+    #[test]
+    fn bfs_finds_shortest_path() {
+        let path = bfs([(0, 0)], moves, |&p| p == (4, 0)).unwrap();
+        assert_eq!(path.cost, 12);
+        assert_eq!(path.steps(), 12);
+        assert_eq!((*path.start(), *path.goal()), ((0, 0), (4, 0)));
+        assert!(path.nodes.windows(2).all(|w| (w[0].0 - w[1].0).abs() + (w[0].1 - w[1].1).abs() == 1));
+    }
+
+    #[test]
+    fn bfs_from_several_starts() {
+        let path = bfs([(0, 0), (3, 0)], moves, |&p| p == (4, 0)).unwrap();
+        assert_eq!(path.nodes, vec![(3, 0), (4, 0)]);
+    }
+
+    #[test]
+    fn bfs_unreachable_goal() {
+        assert_eq!(bfs([(0, 0)], moves, |&p| p == (2, 0)), None);
+    }
+
+    #[test]
+    fn bfs_start_is_goal() {
+        let path = bfs([(1, 1)], moves, |&p| p == (1, 1)).unwrap();
+        assert_eq!((path.cost, path.steps()), (0, 0));
+    }
+
+    #[test]
+    fn bfs_all_distances() {
+        let map = bfs_all([(0, 0)], moves);
+        assert_eq!(map.len(), 21);
+        assert_eq!(map.distance(&(4, 4)), Some(8));
+        assert_eq!(map.distance(&(2, 0)), None);
+        assert!(!map.contains(&(9, 9)));
+        assert_eq!(map.path_to(&(2, 4)).unwrap().steps(), 6);
+    }
+
+    /// A graph where the direct edge is dearer than the detour.
+    fn weighted(&node: &u8) -> Vec<(u8, u32)> {
+        match node {
+            0 => vec![(1, 10), (2, 1)],
+            2 => vec![(3, 2)],
+            3 => vec![(1, 3)],
+            1 => vec![(4, 1)],
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn dijkstra_prefers_cheaper_detour() {
+        let path = dijkstra([0u8], weighted, |&n| n == 4).unwrap();
+        assert_eq!(path.nodes, vec![0, 2, 3, 1, 4]);
+        assert_eq!(path.cost, 7);
+        assert_eq!(dijkstra([4u8], weighted, |&n| n == 0), None);
+    }
+
+    #[test]
+    fn dijkstra_all_distances() {
+        let map = dijkstra_all([0u8], weighted);
+        assert_eq!(map.distance(&1), Some(6));
+        assert_eq!(map.distance(&3), Some(3));
+        assert_eq!(map.into_map().len(), 5);
+    }
+
+    #[test]
+    fn astar_matches_dijkstra() {
+        let goal = (4, 4);
+        let step = |p: &(i32, i32)| moves(p).into_iter().map(|n| (n, 1u32)).collect::<Vec<_>>();
+        let heuristic = |&(x, y): &(i32, i32)| ((goal.0 - x).abs() + (goal.1 - y).abs()) as u32;
+
+        let a = astar([(0, 0)], step, heuristic, |&p| p == goal).unwrap();
+        let d = dijkstra([(0, 0)], step, |&p| p == goal).unwrap();
+        assert_eq!(a.cost, d.cost);
+        assert_eq!(a.cost, 8);
+        assert_eq!(*a.goal(), goal);
+    }
+}
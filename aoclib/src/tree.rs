@@ -0,0 +1,299 @@
+use std::ops::Index;
+
+/// A handle to a node of a [`Tree`], valid for the tree that created it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodeId(usize);
+
+impl NodeId {
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Node<K, V> {
+    key: K,
+    value: V,
+    parent: Option<NodeId>,
+    children: Vec<NodeId>,
+}
+
+/// A rooted tree stored in a single arena. Nodes refer to each other by [`NodeId`], so parent
+/// links don't form reference cycles. Each node has a key, unique among its siblings, used
+/// for path lookups.
+#[derive(Clone, Debug)]
+pub struct Tree<K, V> {
+    nodes: Vec<Node<K, V>>,
+}
+
+// a tree always has its root, so it's never empty
+#[allow(clippy::len_without_is_empty)]
+impl<K: PartialEq, V> Tree<K, V> {
+    pub fn new(root_key: K, root_value: V) -> Self {
+        Self { nodes: vec![Node { key: root_key, value: root_value, parent: None, children: vec![] }] }
+    }
+
+    pub fn root(&self) -> NodeId {
+        NodeId(0)
+    }
+
+    /// The number of nodes reachable from the root, including the root itself. Nodes detached by
+    /// [`Tree::insert`], and anything later added under them, aren't counted, so this walks the tree.
+    pub fn len(&self) -> usize {
+        self.preorder(self.root()).count()
+    }
+
+    pub fn key(&self, id: NodeId) -> &K {
+        &self.nodes[id.0].key
+    }
+
+    pub fn value(&self, id: NodeId) -> &V {
+        &self.nodes[id.0].value
+    }
+
+    pub fn value_mut(&mut self, id: NodeId) -> &mut V {
+        &mut self.nodes[id.0].value
+    }
+
+    pub fn parent(&self, id: NodeId) -> Option<NodeId> {
+        self.nodes[id.0].parent
+    }
+
+    pub fn children(&self, id: NodeId) -> &[NodeId] {
+        &self.nodes[id.0].children
+    }
+
+    pub fn is_leaf(&self, id: NodeId) -> bool {
+        self.nodes[id.0].children.is_empty()
+    }
+
+    pub fn child(&self, parent: NodeId, key: &K) -> Option<NodeId> {
+        self.children(parent).iter().copied().find(|&child| self.key(child) == key)
+    }
+
+    /// Adds a leaf under `parent`. An existing child with the same key is replaced along with its
+    /// whole subtree, which is detached: its ids stay valid but are no longer reachable from `parent`.
+    pub fn insert(&mut self, parent: NodeId, key: K, value: V) -> NodeId {
+        let id = NodeId(self.nodes.len());
+        let replaced = self.child(parent, &key);
+        self.nodes.push(Node { key, value, parent: Some(parent), children: vec![] });
+
+        match replaced {
+            Some(existing) => {
+                self.nodes[existing.0].parent = None;
+
+                // the replacement takes the old child's place among its siblings
+                let siblings = &mut self.nodes[parent.0].children;
+                let position = siblings.iter().position(|&child| child == existing).unwrap();
+                siblings[position] = id;
+            }
+            None => self.nodes[parent.0].children.push(id),
+        }
+
+        id
+    }
+
+    /// The child of `parent` with `key`, added with `value()` if it doesn't exist yet.
+    pub fn child_or_insert_with(&mut self, parent: NodeId, key: K, value: impl FnOnce() -> V) -> NodeId {
+        match self.child(parent, &key) {
+            Some(existing) => existing,
+            None => self.insert(parent, key, value()),
+        }
+    }
+
+    /// Follows `path` down from `from`, one key per level.
+    pub fn lookup<'a>(&self, from: NodeId, path: impl IntoIterator<Item = &'a K>) -> Option<NodeId>
+    where
+        K: 'a,
+    {
+        path.into_iter().try_fold(from, |node, key| self.child(node, key))
+    }
+
+    /// `id` and each of its ancestors, ending with the root.
+    pub fn ancestors(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        std::iter::successors(Some(id), |&node| self.parent(node))
+    }
+
+    pub fn depth(&self, id: NodeId) -> usize {
+        self.ancestors(id).count() - 1
+    }
+
+    /// The keys from the root down to `id`, inclusive.
+    pub fn path(&self, id: NodeId) -> Vec<&K> {
+        let mut keys: Vec<&K> = self.ancestors(id).map(|node| self.key(node)).collect();
+        keys.reverse();
+        keys
+    }
+
+    /// The subtree under `from` with each node before its children.
+    pub fn preorder(&self, from: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        let mut stack = vec![from];
+        std::iter::from_fn(move || {
+            let node = stack.pop()?;
+            stack.extend(self.children(node).iter().rev());
+            Some(node)
+        })
+    }
+
+    /// The subtree under `from` with each node after its children.
+    pub fn postorder(&self, from: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        // (node, whether its children have already been pushed)
+        let mut stack = vec![(from, false)];
+        std::iter::from_fn(move || loop {
+            let (node, expanded) = stack.pop()?;
+            if expanded {
+                return Some(node);
+            }
+
+            stack.push((node, true));
+            stack.extend(self.children(node).iter().rev().map(|&child| (child, false)));
+        })
+    }
+
+    /// Computes a value for every node from its own value and its children's results in a single
+    /// post-order pass, such as subtree sums. The results are cached per node.
+    pub fn aggregate<A>(&self, mut f: impl FnMut(&V, &[&A]) -> A) -> Aggregates<A> {
+        let mut values: Vec<Option<A>> = (0..self.nodes.len()).map(|_| None).collect();
+
+        for node in self.postorder(self.root()) {
+            let result = {
+                let children: Vec<&A> = self.children(node).iter().map(|child| values[child.0].as_ref().unwrap()).collect();
+                f(self.value(node), &children)
+            };
+            values[node.0] = Some(result);
+        }
+
+        Aggregates { values }
+    }
+}
+
+impl<K: PartialEq, V> Index<NodeId> for Tree<K, V> {
+    type Output = V;
+
+    fn index(&self, id: NodeId) -> &V {
+        self.value(id)
+    }
+}
+
+/// Per-node results of [`Tree::aggregate`].
+#[derive(Clone, Debug)]
+pub struct Aggregates<A> {
+    values: Vec<Option<A>>,
+}
+
+impl<A> Aggregates<A> {
+    pub fn get(&self, id: NodeId) -> Option<&A> {
+        self.values.get(id.0)?.as_ref()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (NodeId, &A)> {
+        self.values.iter().enumerate().filter_map(|(i, value)| Some((NodeId(i), value.as_ref()?)))
+    }
+}
+
+impl<A> Index<NodeId> for Aggregates<A> {
+    type Output = A;
+
+    fn index(&self, id: NodeId) -> &A {
+        self.get(id).expect("node is not part of the aggregated tree")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// / (dir) with a/ (dir) holding b = 2 and c = 3, and d = 4 at the top.
+    fn sample() -> (Tree<&'static str, u32>, [NodeId; 4]) {
+        let mut tree = Tree::new("/", 0);
+        let a = tree.insert(tree.root(), "a", 0);
+        let b = tree.insert(a, "b", 2);
+        let c = tree.insert(a, "c", 3);
+        let d = tree.insert(tree.root(), "d", 4);
+        (tree, [a, b, c, d])
+    }
+
+    #[test]
+    fn structure() {
+        let (tree, [a, b, c, d]) = sample();
+        assert_eq!(tree.len(), 5);
+        assert_eq!(tree.children(tree.root()), &[a, d]);
+        assert_eq!(tree.children(a), &[b, c]);
+        assert_eq!(tree.parent(b), Some(a));
+        assert_eq!(tree.parent(tree.root()), None);
+        assert!(tree.is_leaf(c) && !tree.is_leaf(a));
+        assert_eq!((*tree.key(c), tree[c]), ("c", 3));
+        assert_eq!(tree.child(a, &"c"), Some(c));
+        assert_eq!(tree.child(a, &"x"), None);
+    }
+
+    #[test]
+    fn paths() {
+        let (tree, [a, b, _, _]) = sample();
+        assert_eq!(tree.lookup(tree.root(), [&"a", &"b"]), Some(b));
+        assert_eq!(tree.lookup(a, [&"b"]), Some(b));
+        assert_eq!(tree.lookup(tree.root(), [&"a", &"x"]), None);
+        assert_eq!(tree.lookup(a, []), Some(a));
+        assert_eq!(tree.path(b), vec![&"/", &"a", &"b"]);
+        assert_eq!(tree.ancestors(b).collect::<Vec<_>>(), vec![b, a, tree.root()]);
+        assert_eq!((tree.depth(tree.root()), tree.depth(b)), (0, 2));
+    }
+
+    #[test]
+    fn traversals() {
+        let (tree, [a, b, c, d]) = sample();
+        let root = tree.root();
+        assert_eq!(tree.preorder(root).collect::<Vec<_>>(), vec![root, a, b, c, d]);
+        assert_eq!(tree.postorder(root).collect::<Vec<_>>(), vec![b, c, a, d, root]);
+        assert_eq!(tree.postorder(a).collect::<Vec<_>>(), vec![b, c, a]);
+    }
+
+    #[test]
+    fn aggregate_subtree_sums() {
+        let (tree, [a, _, c, _]) = sample();
+        let sums = tree.aggregate(|&value, children: &[&u32]| value + children.iter().copied().sum::<u32>());
+        assert_eq!((sums[tree.root()], sums[a], sums[c]), (9, 5, 3));
+        assert_eq!(sums.iter().count(), 5);
+    }
+
+    #[test]
+    fn child_or_insert_with_keeps_existing() {
+        let (mut tree, [a, ..]) = sample();
+        assert_eq!(tree.child_or_insert_with(tree.root(), "a", || unreachable!()), a);
+        let e = tree.child_or_insert_with(a, "e", || 7);
+        assert_eq!((tree.parent(e), tree[e], tree.len()), (Some(a), 7, 6));
+    }
+
+    #[test]
+    fn insert_replaces_the_whole_subtree() {
+        let (mut tree, [a, b, _, d]) = sample();
+        let replaced = tree.insert(tree.root(), "a", 10);
+
+        assert_ne!(replaced, a);
+        assert!(tree.is_leaf(replaced));
+        assert_eq!(tree.children(tree.root()), &[replaced, d]);
+        assert_eq!(tree.lookup(tree.root(), [&"a", &"b"]), None);
+        assert_eq!(tree.len(), 3);
+        assert_eq!(tree.parent(a), None);
+
+        let sums = tree.aggregate(|&value, children: &[&u32]| value + children.iter().copied().sum::<u32>());
+        assert_eq!(sums[tree.root()], 14);
+        assert_eq!(sums.get(b), None);
+        *tree.value_mut(d) += 1;
+        assert_eq!(tree[d], 5);
+    }
+
+    #[test]
+    fn len_ignores_nodes_added_under_detached_ones() {
+        let (mut tree, [a, b, ..]) = sample();
+        tree.insert(tree.root(), "a", 10);
+        assert_eq!(tree.len(), 3);
+
+        let e = tree.insert(a, "e", 1);
+        tree.insert(b, "f", 1);
+        tree.insert(a, "b", 1);
+        assert_eq!(tree.len(), 3);
+        assert_eq!(tree.path(e), vec![&"a", &"e"]);
+        assert_eq!(tree.preorder(a).count(), 4);
+    }
+}
@@ -0,0 +1,249 @@
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+use std::ops::{Add, Range, Sub};
+
+/// Counts of each item in a sliding window, updated in O(1) as items enter and leave.
+#[derive(Clone, Debug)]
+pub struct WindowCounts<T> {
+    counts: HashMap<T, usize>,
+}
+
+impl<T: Hash + Eq> Default for WindowCounts<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Hash + Eq> WindowCounts<T> {
+    pub fn new() -> Self {
+        Self { counts: HashMap::new() }
+    }
+
+    pub fn push(&mut self, item: T) {
+        *self.counts.entry(item).or_insert(0) += 1;
+    }
+
+    /// Removes one occurrence of `item`, which must be in the window.
+    pub fn pop(&mut self, item: &T) {
+        let count = self.counts.get_mut(item).expect("item is not in the window");
+        *count -= 1;
+        if *count == 0 {
+            self.counts.remove(item);
+        }
+    }
+
+    pub fn count(&self, item: &T) -> usize {
+        self.counts.get(item).copied().unwrap_or(0)
+    }
+
+    /// The number of different items in the window.
+    pub fn distinct(&self) -> usize {
+        self.counts.len()
+    }
+}
+
+/// The first window of `k` bytes that are all different. Uses a count per byte value, so each
+/// step is O(1) regardless of `k`.
+pub fn first_distinct(bytes: &[u8], k: usize) -> Option<Range<usize>> {
+    assert!(k > 0, "window size must be positive");
+
+    let mut counts = [0u32; 256];
+    // how many byte values currently appear more than once
+    let mut repeated = 0;
+
+    for (end, &b) in bytes.iter().enumerate() {
+        counts[b as usize] += 1;
+        if counts[b as usize] == 2 {
+            repeated += 1;
+        }
+
+        if end >= k {
+            let old = bytes[end - k] as usize;
+            counts[old] -= 1;
+            if counts[old] == 1 {
+                repeated -= 1;
+            }
+        }
+
+        if end + 1 >= k && repeated == 0 {
+            return Some(end + 1 - k..end + 1);
+        }
+    }
+
+    None
+}
+
+/// The first window of `k` items that are all different, for any hashable item type.
+pub fn first_distinct_by<T: Hash + Eq>(items: &[T], k: usize) -> Option<Range<usize>> {
+    assert!(k > 0, "window size must be positive");
+
+    let mut counts = WindowCounts::new();
+
+    for (end, item) in items.iter().enumerate() {
+        counts.push(item);
+        if end >= k {
+            counts.pop(&&items[end - k]);
+        }
+
+        if end + 1 >= k && counts.distinct() == k {
+            return Some(end + 1 - k..end + 1);
+        }
+    }
+
+    None
+}
+
+/// The number of different items in each window of `k` items.
+pub fn distinct_counts<T: Hash + Eq>(items: &[T], k: usize) -> Vec<usize> {
+    assert!(k > 0, "window size must be positive");
+
+    let mut counts = WindowCounts::new();
+    let mut result = vec![];
+
+    for (end, item) in items.iter().enumerate() {
+        counts.push(item);
+        if end >= k {
+            counts.pop(&&items[end - k]);
+        }
+        if end + 1 >= k {
+            result.push(counts.distinct());
+        }
+    }
+
+    result
+}
+
+/// The sum of each window of `k` items, keeping a running total.
+pub fn sums<T>(items: &[T], k: usize) -> Vec<T>
+where
+    T: Copy + Default + Add<Output = T> + Sub<Output = T>,
+{
+    assert!(k > 0, "window size must be positive");
+
+    let mut total = T::default();
+    let mut result = vec![];
+
+    for (end, &item) in items.iter().enumerate() {
+        total = total + item;
+        if end >= k {
+            total = total - items[end - k];
+        }
+        if end + 1 >= k {
+            result.push(total);
+        }
+    }
+
+    result
+}
+
+/// The minimum of each window of `k` items.
+pub fn mins<T: Copy + Ord>(items: &[T], k: usize) -> Vec<T> {
+    extrema(items, k, |candidate, kept| candidate <= kept)
+}
+
+/// The maximum of each window of `k` items.
+pub fn maxs<T: Copy + Ord>(items: &[T], k: usize) -> Vec<T> {
+    extrema(items, k, |candidate, kept| candidate >= kept)
+}
+
+/// A monotonic deque: indices whose items can still be the extreme of some window, with the
+/// current extreme at the front. `supersedes(a, b)` is true if `a` makes an earlier `b` useless.
+fn extrema<T: Copy>(items: &[T], k: usize, supersedes: impl Fn(&T, &T) -> bool) -> Vec<T> {
+    assert!(k > 0, "window size must be positive");
+
+    let mut deque: VecDeque<usize> = VecDeque::new();
+    let mut result = vec![];
+
+    for (end, item) in items.iter().enumerate() {
+        while deque.back().is_some_and(|&i| supersedes(item, &items[i])) {
+            deque.pop_back();
+        }
+        deque.push_back(end);
+
+        if deque[0] + k <= end {
+            deque.pop_front();
+        }
+        if end + 1 >= k {
+            result.push(items[deque[0]]);
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn window_counts() {
+        let mut counts = WindowCounts::new();
+        counts.push('a');
+        counts.push('b');
+        counts.push('a');
+        assert_eq!((counts.count(&'a'), counts.count(&'c'), counts.distinct()), (2, 0, 2));
+
+        counts.pop(&'a');
+        counts.pop(&'b');
+        assert_eq!((counts.count(&'a'), counts.distinct()), (1, 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "not in the window")]
+    fn popping_a_missing_item_panics() {
+        WindowCounts::<u8>::default().pop(&1);
+    }
+
+    #[test]
+    fn first_distinct_markers() {
+        let signal = b"mjqjpqmgbljsphdztnvjfqwrcgsmlb";
+        assert_eq!(first_distinct(signal, 4), Some(3..7));
+        assert_eq!(first_distinct(signal, 14), Some(5..19));
+        assert_eq!(first_distinct(b"aaaa", 2), None);
+        assert_eq!(first_distinct(b"ab", 3), None);
+        assert_eq!(first_distinct(b"a", 1), Some(0..1));
+    }
+
+    #[test]
+    fn first_distinct_by_matches_bytes() {
+        let signal = b"nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg";
+        for k in 1..=14 {
+            assert_eq!(first_distinct_by(signal, k), first_distinct(signal, k), "k = {k}");
+        }
+        assert_eq!(first_distinct_by(&["x", "y", "x", "z"], 3), Some(1..4));
+    }
+
+    #[test]
+    fn distinct_counts_per_window() {
+        assert_eq!(distinct_counts(&[1, 1, 2, 3, 3], 3), vec![2, 3, 2]);
+        assert_eq!(distinct_counts(&[1, 2], 3), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn sums_per_window() {
+        assert_eq!(sums(&[1, 2, 3, 4, 5], 2), vec![3, 5, 7, 9]);
+        assert_eq!(sums(&[1.5, -1.0], 1), vec![1.5, -1.0]);
+        assert_eq!(sums(&[1, 2], 3), Vec::<i32>::new());
+    }
+
+    #[test]
+    fn extrema_per_window() {
+        let items = [4, 2, 12, 3, 8, 8, 1, 7];
+        assert_eq!(mins(&items, 3), vec![2, 2, 3, 3, 1, 1]);
+        assert_eq!(maxs(&items, 3), vec![12, 12, 12, 8, 8, 8]);
+        assert_eq!(maxs(&items, 1), items.to_vec());
+        assert_eq!(mins(&items, 8), vec![1]);
+        assert_eq!(mins(&items, 9), Vec::<i32>::new());
+    }
+
+    #[test]
+    fn zero_sized_windows_are_rejected_everywhere() {
+        let panics = |f: fn()| std::panic::catch_unwind(f).is_err();
+        assert!(panics(|| { first_distinct(b"abc", 0); }));
+        assert!(panics(|| { first_distinct_by(&[1, 2], 0); }));
+        assert!(panics(|| { distinct_counts(&[1, 2], 0); }));
+        assert!(panics(|| { sums(&[1, 2], 0); }));
+        assert!(panics(|| { mins(&[1, 2], 0); }));
+        assert!(panics(|| { maxs(&[1, 2], 0); }));
+    }
+}